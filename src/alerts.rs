@@ -0,0 +1,91 @@
+use std::sync::{Arc, Mutex};
+
+use egui_extras::{Column, TableBuilder};
+use sysinfo::Pid;
+
+use crate::{format::format_time, metrics::History};
+
+/// Lists the raised alerts, latest first. Returns the process to show in the
+/// process list, if one was clicked.
+pub fn alerts_view(ctx: &egui::Context, _frame: &mut eframe::Frame, history: Arc<Mutex<History>>) -> Option<Pid> {
+    let mut history = history.lock().unwrap();
+    let alerts = &mut history.alerts;
+    let mut jump = None;
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            if ui.button("Acknowledge all").clicked() {
+                alerts.iter_mut().for_each(|alert| alert.acknowledged = true);
+            }
+            if ui.button("Clear acknowledged").clicked() {
+                alerts.retain(|alert| !alert.acknowledged);
+            }
+            if ui.button("Clear all").clicked() {
+                alerts.clear();
+            }
+        });
+        if alerts.is_empty() {
+            ui.label("No alerts");
+        }
+
+        let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+        let mut cleared = None;
+        TableBuilder::new(ui)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::auto().at_least(128.0))
+            .column(Column::auto().at_least(128.0))
+            .column(Column::auto().at_least(64.0))
+            .column(Column::auto().at_least(192.0))
+            .column(Column::remainder())
+            .min_scrolled_height(0.0)
+            .header(20.0, |mut header| {
+                for title in ["time", "metric", "value", "process", ""] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|body| {
+                let count = alerts.len();
+                body.rows(text_height, count, |row_index, mut row| {
+                    let index = count - 1 - row_index;
+                    let alert = &mut alerts[index];
+                    row.col(|ui| {
+                        ui.label(format_time(alert.time as i64));
+                    });
+                    row.col(|ui| {
+                        if alert.acknowledged {
+                            ui.label(&alert.metric);
+                        } else {
+                            ui.strong(&alert.metric);
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.label(&alert.value);
+                    });
+                    row.col(|ui| {
+                        if let Some((pid, name)) = &alert.process {
+                            if ui
+                                .link(format!("{name} ({pid})"))
+                                .on_hover_text("Show in the process list")
+                                .clicked()
+                            {
+                                jump = Some(*pid);
+                            }
+                        }
+                    });
+                    row.col(|ui| {
+                        if !alert.acknowledged && ui.small_button("Acknowledge").clicked() {
+                            alert.acknowledged = true;
+                        }
+                        if ui.small_button("Clear").clicked() {
+                            cleared = Some(index);
+                        }
+                    });
+                });
+            });
+        if let Some(index) = cleared {
+            alerts.remove(index);
+        }
+    });
+    jump
+}
@@ -1,10 +1,18 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
+};
 
 use egui::{Button, Stroke, Color32};
 use serde::{Serialize, Deserialize};
-use sysinfo::{System, SystemExt};
+use sysinfo::{CpuRefreshKind, Pid, ProcessRefreshKind, System, SystemExt};
 
-use crate::{process_list::ProcessListState, settings::Settings, graphs::GraphsState};
+use crate::{alerts, cli::Args, help::{self, HelpPage}, palette::{self, Command, PaletteState}, cgroups::CgroupsState, connections::ConnectionsState, disks, sensors::SensorsState, sessions::SessionsState, startup::StartupState, keybindings::Action, process_list::ProcessListState, settings::{self, DefaultView, Settings}, graphs::GraphsState, performance, gpu::{GpuState, Gpus}, logs::LogsState, open_files::OpenFilesState, power::PowerState, metrics::History, recorder::Recorder, rules::Watchdog, system_info};
 
 #[derive(Serialize, Deserialize)]
 #[serde(default)]
@@ -14,16 +22,195 @@ pub struct TaskManager {
     #[serde(skip)]
     system: Arc<Mutex<System>>,
 
+    #[serde(skip)]
+    history: Arc<Mutex<History>>,
+
+    #[serde(skip)]
+    gpus: Arc<Gpus>,
+
     #[serde(skip)]
     view: View,
+    /// The state of the views opened before, reused when switching back to
+    /// them.
+    #[serde(skip)]
+    views: HashMap<ViewKind, View>,
+
+    /// Whether the current view shows the processes, read by the refresh
+    /// thread.
+    #[serde(skip)]
+    processes_shown: Arc<AtomicBool>,
+
+    /// Settings file of the portable mode, used instead of the platform
+    /// storage.
+    #[serde(skip)]
+    portable: Option<PathBuf>,
+    /// When the portable settings file was last read or written by the app,
+    /// so that only outside edits are reloaded.
+    #[serde(skip)]
+    portable_modified: Arc<Mutex<Option<SystemTime>>>,
+
+    #[serde(skip)]
+    first_frame: bool,
+    /// Set by File > Quit, so that closing is not turned into minimizing.
+    #[serde(skip)]
+    quitting: bool,
+    #[serde(skip)]
+    minimize: bool,
+    /// Icon in the system tray, if one could be added.
+    #[cfg(feature = "tray")]
+    #[serde(skip)]
+    tray: Option<crate::tray::Tray>,
+    /// Whether the window is hidden, only the tray icon being left.
+    #[cfg(feature = "tray")]
+    #[serde(skip)]
+    hidden: bool,
+    /// Page of the help window, which is closed when `None`.
+    #[serde(skip)]
+    help: Option<HelpPage>,
+    #[serde(skip)]
+    palette: Option<PaletteState>,
 }
 
 pub enum View {
     Processes(ProcessListState),
+    Performance,
     Graphs(GraphsState),
+    Alerts,
+    Cgroups(CgroupsState),
+    Connections(ConnectionsState),
+    #[cfg(unix)]
+    Containers(crate::containers::ContainersState),
+    Disks,
+    Gpu(GpuState),
+    Logs(LogsState),
+    OpenFiles(OpenFilesState),
+    Power(PowerState),
+    Sensors(SensorsState),
+    Startup(StartupState),
+    Sessions(SessionsState),
+    #[cfg(feature = "systemd")]
+    Services(crate::services::ServicesState),
+    SystemInfo,
     Settings,
 }
 
+/// The views, without their state, to open them by name.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ViewKind {
+    Processes,
+    Performance,
+    Graphs,
+    Alerts,
+    Cgroups,
+    Connections,
+    #[cfg(unix)]
+    Containers,
+    Disks,
+    Gpu,
+    Logs,
+    OpenFiles,
+    Power,
+    Sensors,
+    Sessions,
+    Startup,
+    #[cfg(feature = "systemd")]
+    Services,
+    SystemInfo,
+    Settings,
+}
+
+impl ViewKind {
+    pub const ALL: &'static [ViewKind] = &[
+        ViewKind::Processes,
+        ViewKind::Performance,
+        ViewKind::Graphs,
+        ViewKind::Alerts,
+        ViewKind::Cgroups,
+        ViewKind::Connections,
+        #[cfg(unix)]
+        ViewKind::Containers,
+        ViewKind::Disks,
+        ViewKind::Gpu,
+        ViewKind::Logs,
+        ViewKind::OpenFiles,
+        ViewKind::Power,
+        ViewKind::Sensors,
+        ViewKind::Sessions,
+        ViewKind::Startup,
+        #[cfg(feature = "systemd")]
+        ViewKind::Services,
+        ViewKind::SystemInfo,
+        ViewKind::Settings,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ViewKind::Processes => "Processes",
+            ViewKind::Performance => "Performance",
+            ViewKind::Graphs => "Graphs",
+            ViewKind::Alerts => "Alerts",
+            ViewKind::Cgroups => "Control groups",
+            ViewKind::Connections => "Connections",
+            #[cfg(unix)]
+            ViewKind::Containers => "Containers",
+            ViewKind::Disks => "Disks",
+            ViewKind::Gpu => "GPU",
+            ViewKind::Logs => "Logs",
+            ViewKind::OpenFiles => "Open files",
+            ViewKind::Power => "Power",
+            ViewKind::Sensors => "Sensors",
+            ViewKind::Startup => "Startup",
+            ViewKind::Sessions => "Users",
+            #[cfg(feature = "systemd")]
+            ViewKind::Services => "Services",
+            ViewKind::SystemInfo => "System information",
+            ViewKind::Settings => "Settings",
+        }
+    }
+
+    /// Whether the view reads the processes, which must then be refreshed.
+    pub fn needs_processes(&self) -> bool {
+        match self {
+            ViewKind::Processes
+            | ViewKind::Cgroups
+            | ViewKind::Connections
+            | ViewKind::Gpu
+            | ViewKind::OpenFiles
+            | ViewKind::Sessions => true,
+            #[cfg(unix)]
+            ViewKind::Containers => true,
+            _ => false,
+        }
+    }
+}
+
+impl View {
+    fn kind(&self) -> ViewKind {
+        match self {
+            View::Processes(_) => ViewKind::Processes,
+            View::Performance => ViewKind::Performance,
+            View::Graphs(_) => ViewKind::Graphs,
+            View::Alerts => ViewKind::Alerts,
+            View::Cgroups(_) => ViewKind::Cgroups,
+            View::Connections(_) => ViewKind::Connections,
+            #[cfg(unix)]
+            View::Containers(_) => ViewKind::Containers,
+            View::Disks => ViewKind::Disks,
+            View::Gpu(_) => ViewKind::Gpu,
+            View::Logs(_) => ViewKind::Logs,
+            View::OpenFiles(_) => ViewKind::OpenFiles,
+            View::Power(_) => ViewKind::Power,
+            View::Sensors(_) => ViewKind::Sensors,
+            View::Startup(_) => ViewKind::Startup,
+            View::Sessions(_) => ViewKind::Sessions,
+            #[cfg(feature = "systemd")]
+            View::Services(_) => ViewKind::Services,
+            View::SystemInfo => ViewKind::SystemInfo,
+            View::Settings => ViewKind::Settings,
+        }
+    }
+}
+
 impl Default for View {
     fn default() -> Self {
         Self::Processes(ProcessListState::default())
@@ -35,26 +222,134 @@ impl Default for TaskManager {
         Self {
             settings: Arc::new(Mutex::new(Settings::default())),
             system: Arc::new(Mutex::new(sysinfo::System::new_all())),
+            history: Default::default(),
+            gpus: Default::default(),
             view: View::Processes(ProcessListState::default()),
+            views: HashMap::new(),
+            processes_shown: Default::default(),
+            portable: None,
+            portable_modified: Default::default(),
+            first_frame: true,
+            quitting: false,
+            minimize: false,
+            #[cfg(feature = "tray")]
+            tray: None,
+            #[cfg(feature = "tray")]
+            hidden: false,
+            help: None,
+            palette: None,
         }
     }
 }
 
 impl TaskManager {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        let tm = TaskManager::default();
+    pub fn new(cc: &eframe::CreationContext<'_>, args: Args) -> Self {
+        // Restore the settings saved by `save` on the previous run.
+        let mut tm: TaskManager = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+            .unwrap_or_default();
+        tm.settings.lock().unwrap().migrate();
+        tm.portable = settings::portable_path();
+        if let Some(path) = tm.portable.clone() {
+            if let Err(error) = tm.settings.lock().unwrap().import(&path) {
+                eprintln!("Could not read {}: {error}", path.display());
+            }
+            *tm.portable_modified.lock().unwrap() = settings::modified(&path);
+            tm.watch_portable(path, cc.egui_ctx.clone());
+        }
+        #[cfg(feature = "tray")]
+        {
+            tm.tray = match crate::tray::Tray::new(cc.egui_ctx.clone(), tm.system.clone(), tm.settings.clone()) {
+                Ok(tray) => Some(tray),
+                Err(error) => {
+                    eprintln!("Could not add the tray icon: {error}");
+                    None
+                }
+            };
+        }
+        let default_view = tm.settings.lock().unwrap().default_view;
+        tm.view = match default_view {
+            DefaultView::Processes => tm.processes_view(),
+            DefaultView::Performance => View::Performance,
+            DefaultView::Graphs => View::Graphs(GraphsState::default()),
+        };
+        if let Some(filter) = args.filter {
+            tm.process_list().set_search(filter);
+        }
+        if let Some(kind) = args.view {
+            tm.open(kind);
+        }
 
         std::thread::spawn({
             let system = tm.system.clone();
             let settings = tm.settings.clone();
+            let history = tm.history.clone();
+            let gpus = tm.gpus.clone();
+            let processes_shown = tm.processes_shown.clone();
+            let ctx = cc.egui_ctx.clone();
             move || {
+                // When the processes, the graphs and the sensors are next due
+                // for a refresh.
+                let mut due = [Instant::now(); 3];
+                #[cfg(feature = "notifications")]
+                let mut notifier = crate::notifications::Notifier::default();
+                let mut watchdog = Watchdog::default();
+                let mut recorder = Recorder::default();
                 loop {
-                    let time = {
+                    let (intervals, processes_in_background, retention, thresholds, temperature_unit, rules, byte_units) = {
                         let settings = settings.lock().unwrap();
-                        settings.update_interval_ms
+                        let intervals = settings.refresh_intervals;
+                        (
+                            match args.interval {
+                                Some(interval) => [interval; 3],
+                                None => [intervals.processes_ms, intervals.graphs_ms, intervals.sensors_ms],
+                            },
+                            intervals.processes_in_background,
+                            settings.graph_retention.secs(),
+                            settings.thresholds,
+                            settings.temperature_unit,
+                            settings.rules.iter().filter(|rule| rule.enabled).cloned().collect::<Vec<_>>(),
+                            settings.byte_units,
+                        )
                     };
-                    std::thread::sleep(std::time::Duration::from_millis(time as u64));
-                    system.lock().unwrap().refresh_all();
+                    let recording = settings.lock().unwrap().recording.clone();
+                    #[cfg(feature = "notifications")]
+                    let notifications = settings.lock().unwrap().notifications;
+                    let wake_up = due.iter().min().copied().unwrap();
+                    std::thread::sleep(wake_up.saturating_duration_since(Instant::now()));
+                    let now = Instant::now();
+                    let [processes, graphs, sensors] = due.map(|time| time <= now);
+                    let mut system = system.lock().unwrap();
+                    // The rules need the processes to be up to date, even in
+                    // the background.
+                    if processes && (processes_in_background || !rules.is_empty() || processes_shown.load(Ordering::Relaxed)) {
+                        refresh_processes(&mut system);
+                        watchdog.check(&rules, &system, &history, byte_units);
+                    }
+                    if sensors {
+                        system.refresh_components();
+                    }
+                    if graphs {
+                        system.refresh_cpu_specifics(CpuRefreshKind::everything());
+                        system.refresh_memory();
+                        system.refresh_networks_list();
+                        system.refresh_disks_list();
+                        {
+                            let mut history = history.lock().unwrap();
+                            history.record(&system, &gpus, retention, &thresholds, temperature_unit);
+                            recorder.record(&system, &history, &recording);
+                        }
+                        #[cfg(feature = "notifications")]
+                        notifier.check(&system, &thresholds, temperature_unit, &notifications);
+                    }
+                    drop(system);
+                    for ((due, refreshed), interval) in due.iter_mut().zip([processes, graphs, sensors]).zip(intervals) {
+                        if refreshed {
+                            *due = now + Duration::from_millis(interval);
+                        }
+                    }
+                    ctx.request_repaint();
                 }
             }
         });
@@ -63,55 +358,323 @@ impl TaskManager {
     }
 }
 
+impl TaskManager {
+    /// Reloads the portable settings file whenever it is edited outside of
+    /// the app.
+    fn watch_portable(&self, path: PathBuf, ctx: egui::Context) {
+        let settings = self.settings.clone();
+        let portable_modified = self.portable_modified.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(Duration::from_secs(1));
+            let modified = settings::modified(&path);
+            {
+                let mut last = portable_modified.lock().unwrap();
+                if *last == modified {
+                    continue;
+                }
+                *last = modified;
+            }
+            if settings.lock().unwrap().import(&path).is_ok() {
+                ctx.request_repaint();
+            }
+        });
+    }
+}
+
+/// Refreshes what the app shows of the processes, leaving out their disk
+/// usage. The users are refreshed too, for the owners of new processes.
+fn refresh_processes(system: &mut System) {
+    system.refresh_processes_specifics(ProcessRefreshKind::new().with_cpu().with_user());
+    system.refresh_users_list();
+}
+
 impl eframe::App for TaskManager {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let actions = {
+            let settings = self.settings.lock().unwrap();
+            settings.apply(ctx, frame);
+            settings.keybindings.triggered(ctx)
+        };
+        for action in actions {
+            self.run(action, frame);
+        }
+        if std::mem::take(&mut self.first_frame) && self.settings.lock().unwrap().window.start_minimized {
+            self.minimize = true;
+        }
+        #[cfg(feature = "tray")]
+        self.handle_tray(frame);
+        if std::mem::take(&mut self.minimize) {
+            frame.set_minimized(true);
+        }
+        self.processes_shown.store(self.view.kind().needs_processes(), Ordering::Relaxed);
 
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             self.top_panel(ui, frame);
         });
+        help::help_window(ctx, &mut self.help, &self.settings.lock().unwrap().keybindings);
+        if let Some(command) = palette::palette_window(ctx, &mut self.palette) {
+            self.run_command(command, frame);
+        }
 
         match &mut self.view {
-            View::Processes(state) => state.process_list_view(ctx, frame, self.system.clone()),
-            View::Graphs(state) => state.graphs_view(ctx, frame, self.system.clone()),
+            View::Processes(state) => state.process_list_view(ctx, frame, self.system.clone(), self.history.clone(), self.settings.clone()),
+            View::Performance => {
+                if let Some(kind) = performance::performance_view(ctx, frame, self.history.clone(), self.settings.clone()) {
+                    self.open(ViewKind::Graphs);
+                    if let View::Graphs(state) = &mut self.view {
+                        state.focus(kind);
+                    }
+                }
+            }
+            View::Graphs(state) => state.graphs_view(ctx, frame, self.history.clone(), self.settings.clone()),
+            View::Alerts => {
+                if let Some(pid) = alerts::alerts_view(ctx, frame, self.history.clone()) {
+                    self.show_process(pid);
+                }
+            }
+            View::Cgroups(state) => {
+                if let Some(pid) = state.cgroups_view(ctx, frame, self.system.clone(), self.settings.clone()) {
+                    self.show_process(pid);
+                }
+            }
+            View::Connections(state) => {
+                if let Some(pid) = state.connections_view(ctx, frame, self.system.clone(), self.settings.clone()) {
+                    self.show_process(pid);
+                }
+            }
+            View::Disks => disks::disks_view(ctx, frame, self.system.clone(), self.settings.clone()),
+            #[cfg(unix)]
+            View::Containers(state) => {
+                if let Some((name, pids)) = state.containers_view(ctx, frame, self.settings.clone()) {
+                    self.process_list().show_only(name, pids);
+                }
+            }
+            View::Gpu(state) => {
+                if let Some(pid) = state.gpu_view(ctx, frame, self.gpus.clone(), self.system.clone(), self.settings.clone()) {
+                    self.show_process(pid);
+                }
+            }
+            View::Logs(state) => {
+                if let Some(pid) = state.logs_view(ctx, frame) {
+                    self.show_process(pid);
+                }
+            }
+            View::OpenFiles(state) => {
+                if let Some(pid) = state.open_files_view(ctx, frame, self.system.clone()) {
+                    self.show_process(pid);
+                }
+            }
+            View::Power(state) => {
+                if let Some(pid) = state.power_view(ctx, frame, self.settings.clone()) {
+                    self.show_process(pid);
+                }
+            }
+            View::Sensors(state) => state.sensors_view(ctx, frame, self.system.clone(), self.settings.clone()),
+            View::Startup(state) => state.startup_view(ctx, frame),
+            View::Sessions(state) => {
+                if let Some(owner) = state.sessions_view(ctx, frame, self.system.clone(), self.settings.clone()) {
+                    self.process_list().search_owner(&owner);
+                }
+            }
+            #[cfg(feature = "systemd")]
+            View::Services(state) => state.services_view(ctx, frame, self.settings.clone()),
+            View::SystemInfo => system_info::system_info_view(ctx, frame, self.system.clone(), self.settings.clone()),
             View::Settings => self.settings.lock().unwrap().settings_view(ctx, frame),
         }
     }
+
+    fn on_close_event(&mut self) -> bool {
+        if self.quitting || !self.settings.lock().unwrap().window.minimize_on_close {
+            return true;
+        }
+        self.minimize = true;
+        false
+    }
+
+    /// Called by eframe on exit and every few seconds, saves the settings to
+    /// the platform data directory.
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        match &self.portable {
+            Some(path) => {
+                if self.settings.lock().unwrap().export(path).is_ok() {
+                    *self.portable_modified.lock().unwrap() = settings::modified(path);
+                }
+            }
+            None => eframe::set_value(storage, eframe::APP_KEY, self),
+        }
+    }
+
+    fn auto_save_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(5)
+    }
 }
 
 impl TaskManager {
+    /// A new process list, sorted as chosen in the settings.
+    fn processes_view(&self) -> View {
+        let settings = self.settings.lock().unwrap();
+        View::Processes(ProcessListState::new(settings.sort_column, settings.sort_order))
+    }
+
+    /// Shows or hides the window, or quits, as asked from the tray icon. The
+    /// window is hidden instead of minimized while there is one.
+    #[cfg(feature = "tray")]
+    fn handle_tray(&mut self, frame: &mut eframe::Frame) {
+        let Some(tray) = &self.tray else {
+            return;
+        };
+        let mut hidden = self.hidden || std::mem::take(&mut self.minimize);
+        for request in tray.requests() {
+            match request {
+                crate::tray::TrayRequest::ToggleWindow => hidden = !hidden,
+                crate::tray::TrayRequest::Quit => {
+                    self.quitting = true;
+                    frame.close();
+                }
+            }
+        }
+        if hidden != self.hidden {
+            self.hidden = hidden;
+            frame.set_visible(!hidden);
+            tray.set_visible(!hidden);
+        }
+    }
+
+    /// Switches to a view, with the state it was left in if it was opened
+    /// before.
+    fn open(&mut self, kind: ViewKind) {
+        if self.view.kind() == kind {
+            return;
+        }
+        let view = match self.views.remove(&kind) {
+            Some(view) => view,
+            None => self.new_view(kind),
+        };
+        let previous = std::mem::replace(&mut self.view, view);
+        self.views.insert(previous.kind(), previous);
+    }
+
+    fn new_view(&self, kind: ViewKind) -> View {
+        match kind {
+            ViewKind::Processes => self.processes_view(),
+            ViewKind::Performance => View::Performance,
+            ViewKind::Graphs => View::Graphs(GraphsState::default()),
+            ViewKind::Alerts => View::Alerts,
+            ViewKind::Cgroups => View::Cgroups(CgroupsState::default()),
+            ViewKind::Connections => View::Connections(ConnectionsState::default()),
+            #[cfg(unix)]
+            ViewKind::Containers => View::Containers(Default::default()),
+            ViewKind::Disks => View::Disks,
+            ViewKind::Gpu => View::Gpu(GpuState::default()),
+            ViewKind::Logs => View::Logs(LogsState::new()),
+            ViewKind::OpenFiles => View::OpenFiles(OpenFilesState::default()),
+            ViewKind::Power => View::Power(PowerState::default()),
+            ViewKind::Sensors => View::Sensors(SensorsState::default()),
+            ViewKind::Startup => View::Startup(StartupState::default()),
+            ViewKind::Sessions => View::Sessions(SessionsState::default()),
+            #[cfg(feature = "systemd")]
+            ViewKind::Services => View::Services(Default::default()),
+            ViewKind::SystemInfo => View::SystemInfo,
+            ViewKind::Settings => View::Settings,
+        }
+    }
+
+    /// The process list, opened if another view is shown.
+    fn process_list(&mut self) -> &mut ProcessListState {
+        self.open(ViewKind::Processes);
+        match &mut self.view {
+            View::Processes(state) => state,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Opens the process list with a process selected.
+    fn show_process(&mut self, pid: Pid) {
+        self.process_list().select(pid);
+    }
+
+    fn run(&mut self, action: Action, frame: &mut eframe::Frame) {
+        match action {
+            Action::KillSelected => {
+                if let View::Processes(state) = &mut self.view {
+                    state.kill_selected();
+                }
+            }
+            Action::FocusSearch => self.process_list().focus_search(),
+            Action::ProcessesView => self.open(ViewKind::Processes),
+            Action::GraphsView => self.open(ViewKind::Graphs),
+            Action::PerformanceView => self.open(ViewKind::Performance),
+            Action::Refresh => {
+                let mut system = self.system.lock().unwrap();
+                refresh_processes(&mut system);
+                system.refresh_cpu();
+                system.refresh_memory();
+            }
+            Action::Quit => {
+                self.quitting = true;
+                frame.close();
+            }
+            Action::CommandPalette => {
+                self.palette = match self.palette {
+                    Some(_) => None,
+                    None => Some(PaletteState::default()),
+                };
+            }
+        }
+    }
+
+    fn run_command(&mut self, command: Command, frame: &mut eframe::Frame) {
+        match command {
+            Command::Open(kind) => self.open(kind),
+            Command::Run(action) => self.run(action, frame),
+            Command::ToggleRegex => self.process_list().toggle_regex(),
+            Command::ToggleLabelSearch => self.process_list().toggle_label_search(),
+            Command::ToggleCaseSensitive => self.process_list().toggle_case_sensitive(),
+            Command::QuickFilter(filter) => self.process_list().toggle_quick_filter(filter),
+            Command::Help(page) => self.help = Some(page),
+        }
+    }
+
     fn top_panel(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame) {
         egui::menu::bar(ui, |ui| {
             ui.menu_button("File", |ui| {
+                if ui.button("Command palette").clicked() {
+                    self.palette = Some(PaletteState::default());
+                    ui.close_menu();
+                }
                 if ui.button("Settings").clicked() {
-                    self.view = View::Settings;
+                    self.open(ViewKind::Settings);
                 }
                 if ui.button("Quit").clicked() {
+                    self.quitting = true;
                     frame.close();
                 }
             });
             ui.menu_button("Views", |ui| {
-                let mut processes_btn = Button::new("Processes");
-                let mut graphs_btn = Button::new("Graphs");
-                match self.view {
-                    View::Processes(_) => {
-                        processes_btn = processes_btn.stroke(Stroke::new(2.0, Color32::DARK_GRAY));
-                    },
-                    View::Graphs(_) => {
-                        graphs_btn = graphs_btn.stroke(Stroke::new(2.0, Color32::DARK_GRAY));
+                let unacknowledged = self.history.lock().unwrap().alerts.iter().filter(|alert| !alert.acknowledged).count();
+                for kind in ViewKind::ALL.iter().filter(|kind| **kind != ViewKind::Settings) {
+                    let label = match kind {
+                        ViewKind::Alerts if unacknowledged > 0 => format!("Alerts ({unacknowledged})"),
+                        _ => kind.label().to_string(),
+                    };
+                    let stroke = if self.view.kind() == *kind {
+                        Stroke::new(2.0, Color32::DARK_GRAY)
+                    } else {
+                        Stroke::NONE
+                    };
+                    if ui.add(Button::new(label).stroke(stroke)).clicked() {
+                        self.open(*kind);
+                        ui.close_menu();
                     }
-                    _ => {}
-                };
-                if ui.add(processes_btn).clicked() {
-                    self.view = View::Processes(ProcessListState::default());
-                    ui.close_menu();
-                }
-                if ui.add(graphs_btn).clicked() {
-                    self.view = View::Graphs(GraphsState::default());
-                    ui.close_menu();
                 }
             });
-            ui.menu_button("Help", |_| {
-                frame.close();
+            ui.menu_button("Help", |ui| {
+                for page in HelpPage::ALL {
+                    if ui.button(page.label()).clicked() {
+                        self.help = Some(page);
+                        ui.close_menu();
+                    }
+                }
             });
         });
     }
@@ -0,0 +1,133 @@
+#[cfg(target_os = "linux")]
+use std::{env, fs, path::PathBuf};
+
+/// An XDG autostart entry, from the directory of the user or from the
+/// system ones.
+pub struct Entry {
+    /// Name of the `.desktop` file, which identifies the entry.
+    pub file_name: String,
+    pub name: String,
+    pub exec: String,
+    pub enabled: bool,
+    /// Where the entry is read from, the file of the user overriding the
+    /// system one.
+    #[cfg(target_os = "linux")]
+    path: PathBuf,
+}
+
+/// Starts the app on login through an XDG autostart entry, or stops doing so.
+#[cfg(target_os = "linux")]
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    let path = desktop_entry().ok_or_else(|| "No configuration directory".to_string())?;
+    if !enabled {
+        return match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        };
+    }
+    let executable = env::current_exe().map_err(|e| e.to_string())?;
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=Task Manager\nExec=\"{}\"\nX-GNOME-Autostart-enabled=true\n",
+        executable.display()
+    );
+    fs::create_dir_all(path.parent().unwrap()).map_err(|e| e.to_string())?;
+    fs::write(&path, entry).map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_enabled(_enabled: bool) -> Result<(), String> {
+    Err("Launching on login is only supported on Linux".to_string())
+}
+
+/// The autostart entries, sorted by name.
+#[cfg(target_os = "linux")]
+pub fn entries() -> Vec<Entry> {
+    let system_dirs = env::var("XDG_CONFIG_DIRS").unwrap_or_else(|_| "/etc/xdg".to_string());
+    // Later directories override earlier ones, so the user one comes last.
+    let mut dirs = system_dirs
+        .split(':')
+        .rev()
+        .map(|dir| PathBuf::from(dir).join("autostart"))
+        .collect::<Vec<_>>();
+    dirs.extend(user_dir());
+    let mut entries: Vec<Entry> = vec![];
+    for dir in dirs {
+        let Ok(files) = fs::read_dir(dir) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let file_name = file.file_name().to_string_lossy().to_string();
+            if !file_name.ends_with(".desktop") {
+                continue;
+            }
+            let Some(entry) = read_entry(file_name, file.path()) else {
+                continue;
+            };
+            entries.retain(|other| other.file_name != entry.file_name);
+            entries.push(entry);
+        }
+    }
+    entries.sort_by_key(|entry| entry.name.to_lowercase());
+    entries
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn entries() -> Vec<Entry> {
+    vec![]
+}
+
+#[cfg(target_os = "linux")]
+fn read_entry(file_name: String, path: PathBuf) -> Option<Entry> {
+    let contents = fs::read_to_string(&path).ok()?;
+    let value = |key: &str| {
+        contents
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+            .map(|value| value.trim().to_string())
+    };
+    Some(Entry {
+        name: value("Name").unwrap_or_else(|| file_name.trim_end_matches(".desktop").to_string()),
+        exec: value("Exec").unwrap_or_default(),
+        enabled: value("Hidden").as_deref() != Some("true")
+            && value("X-GNOME-Autostart-enabled").as_deref() != Some("false"),
+        file_name,
+        path,
+    })
+}
+
+/// Enables or disables an autostart entry. As system entries can not be
+/// edited, a copy of them is written in the directory of the user, which
+/// takes precedence.
+#[cfg(target_os = "linux")]
+pub fn set_entry_enabled(entry: &Entry, enabled: bool) -> Result<(), String> {
+    let contents = fs::read_to_string(&entry.path).map_err(|e| e.to_string())?;
+    let mut lines = contents
+        .lines()
+        .filter(|line| !line.starts_with("Hidden=") && !line.starts_with("X-GNOME-Autostart-enabled="))
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    let header = lines.iter().position(|line| line.trim() == "[Desktop Entry]").unwrap_or(0);
+    lines.insert(header + 1, format!("Hidden={}", !enabled));
+    let dir = user_dir().ok_or_else(|| "No configuration directory".to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    fs::write(dir.join(&entry.file_name), lines.join("\n") + "\n").map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_entry_enabled(_entry: &Entry, _enabled: bool) -> Result<(), String> {
+    Err("Autostart entries are only supported on Linux".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn desktop_entry() -> Option<PathBuf> {
+    Some(user_dir()?.join("task-manager.desktop"))
+}
+
+/// The autostart directory of the user.
+#[cfg(target_os = "linux")]
+fn user_dir() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("autostart"))
+}
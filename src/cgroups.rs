@@ -0,0 +1,297 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use egui_extras::{Column, TableBuilder};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+use crate::settings::Settings;
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// A control group of the unified (v2) hierarchy, with the resources used
+/// by all the processes under it.
+pub struct Cgroup {
+    /// Path from the root of the hierarchy, such as `/system.slice/sshd.service`.
+    pub path: String,
+    /// CPU time used since the group was created, in microseconds.
+    pub cpu_usec: Option<u64>,
+    /// Memory in use, in bytes.
+    pub memory: Option<u64>,
+    /// Bytes read and written since the group was created.
+    pub io: Option<[u64; 2]>,
+    /// Processes directly in this group.
+    pub pids: Vec<Pid>,
+    pub children: Vec<Cgroup>,
+}
+
+impl Cgroup {
+    fn name(&self) -> &str {
+        self.path.rsplit('/').next().filter(|name| !name.is_empty()).unwrap_or("/")
+    }
+
+    /// Number of processes in the group and its descendants.
+    fn process_count(&self) -> usize {
+        self.pids.len() + self.children.iter().map(Cgroup::process_count).sum::<usize>()
+    }
+}
+
+/// The tree of control groups, if the unified hierarchy is mounted.
+pub fn cgroups() -> Option<Cgroup> {
+    let root = Path::new(CGROUP_ROOT);
+    root.join("cgroup.controllers").exists().then(|| read_cgroup(root, "/".to_string()))
+}
+
+fn read_cgroup(dir: &Path, path: String) -> Cgroup {
+    let read = |file: &str| fs::read_to_string(dir.join(file)).ok();
+    // The root group has no usage files, but the whole machine is in it.
+    let io = read("io.stat").map(|stat| {
+        let mut totals = [0, 0];
+        for (key, value) in stat.split_whitespace().filter_map(|field| field.split_once('=')) {
+            let index = match key {
+                "rbytes" => 0,
+                "wbytes" => 1,
+                _ => continue,
+            };
+            totals[index] += value.parse::<u64>().unwrap_or(0);
+        }
+        totals
+    });
+    let mut children = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.file_type().map_or(false, |kind| kind.is_dir()))
+                .map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    read_cgroup(&entry.path(), format!("{}/{name}", path.trim_end_matches('/')))
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    children.sort_by(|a, b| a.path.cmp(&b.path));
+    Cgroup {
+        cpu_usec: read("cpu.stat")
+            .and_then(|stat| stat.lines().find_map(|line| line.strip_prefix("usage_usec ")?.parse().ok())),
+        memory: read("memory.current").and_then(|memory| memory.trim().parse().ok()),
+        io,
+        pids: read("cgroup.procs")
+            .map(|procs| procs.lines().filter_map(|pid| pid.parse().ok()).map(Pid::from_u32).collect())
+            .unwrap_or_default(),
+        path,
+        children,
+    }
+}
+
+/// A row of the table: a group or one of its processes, with its depth.
+enum Row<'a> {
+    Cgroup(&'a Cgroup, usize),
+    Process(Pid, usize),
+}
+
+/// The hierarchy read by a thread, with when it was.
+type Listing = (Instant, Option<Cgroup>);
+
+#[derive(Default)]
+pub struct CgroupsState {
+    root: Option<Cgroup>,
+    /// Usage of each group at the previous refresh, to compute rates.
+    previous: HashMap<String, (u64, [u64; 2])>,
+    /// CPU usage in percent of one core, and read and write rates in bytes
+    /// per second, of each group since the previous refresh.
+    rates: HashMap<String, (f64, [f64; 2])>,
+    /// Paths of the groups whose children are shown.
+    expanded: HashSet<String>,
+    refreshed: Option<Instant>,
+    /// When the shown hierarchy was read, to compute the rates.
+    listed_at: Option<Instant>,
+    /// Whether a thread is going through `/sys/fs/cgroup`, which can take a
+    /// while with many groups.
+    listing: bool,
+    /// Set by the thread reading it.
+    listed: Arc<Mutex<Option<Listing>>>,
+}
+
+impl CgroupsState {
+    fn update(&mut self, (time, root): Listing) {
+        let elapsed = self.listed_at.map(|previous| time.duration_since(previous).as_secs_f64());
+        self.root = root;
+        let mut current = HashMap::new();
+        let mut stack = self.root.iter().collect::<Vec<_>>();
+        while let Some(cgroup) = stack.pop() {
+            stack.extend(&cgroup.children);
+            if let (Some(cpu), Some(io)) = (cgroup.cpu_usec, cgroup.io) {
+                current.insert(cgroup.path.clone(), (cpu, io));
+            }
+        }
+        self.rates.clear();
+        if let Some(elapsed) = elapsed {
+            for (path, (cpu, io)) in &current {
+                if let Some((previous_cpu, previous_io)) = self.previous.get(path) {
+                    let cpu = cpu.saturating_sub(*previous_cpu) as f64 / 1e6 / elapsed * 100.0;
+                    let io = [0, 1].map(|index| io[index].saturating_sub(previous_io[index]) as f64 / elapsed);
+                    self.rates.insert(path.clone(), (cpu, io));
+                }
+            }
+        }
+        self.previous = current;
+        self.listed_at = Some(time);
+    }
+
+    /// Shows the resources used by each control group, like systemd-cgtop.
+    /// Returns the process to show in the process list, if one was clicked.
+    pub fn cgroups_view(
+        &mut self,
+        ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        system: Arc<Mutex<System>>,
+        settings: Arc<Mutex<Settings>>,
+    ) -> Option<Pid> {
+        let (interval, byte_units) = {
+            let settings = settings.lock().unwrap();
+            (Duration::from_millis(settings.refresh_intervals.processes_ms), settings.byte_units)
+        };
+        let listing = self.listed.lock().unwrap().take();
+        if let Some(listing) = listing {
+            self.listing = false;
+            self.update(listing);
+        }
+        if !self.listing && self.refreshed.map_or(true, |time| time.elapsed() >= interval) {
+            self.listing = true;
+            self.refreshed = Some(Instant::now());
+            let listed = self.listed.clone();
+            let ctx = ctx.clone();
+            std::thread::spawn(move || {
+                let root = cgroups();
+                *listed.lock().unwrap() = Some((Instant::now(), root));
+                ctx.request_repaint();
+            });
+        }
+        ctx.request_repaint_after(interval);
+        let (mib, mib_name) = byte_units.unit(2);
+        let (kib, kib_name) = byte_units.unit(1);
+
+        let system = system.lock().unwrap();
+        let mut jump = None;
+        let mut toggled = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let Some(root) = &self.root else {
+                if self.listed_at.is_some() {
+                    ui.label("The unified cgroup hierarchy (cgroups v2) is not mounted");
+                }
+                return;
+            };
+            let mut rows = vec![];
+            let mut stack = vec![Row::Cgroup(root, 0)];
+            while let Some(row) = stack.pop() {
+                if let Row::Cgroup(cgroup, depth) = row {
+                    if self.expanded.contains(&cgroup.path) || depth == 0 {
+                        // Pushed in reverse, to be shown in order.
+                        for pid in cgroup.pids.iter().rev() {
+                            stack.push(Row::Process(*pid, depth + 1));
+                        }
+                        let mut children = cgroup.children.iter().collect::<Vec<_>>();
+                        children.sort_by(|a, b| {
+                            let cpu = |cgroup: &Cgroup| self.rates.get(&cgroup.path).map_or(0.0, |rates| rates.0);
+                            cpu(a).total_cmp(&cpu(b))
+                        });
+                        for child in children {
+                            stack.push(Row::Cgroup(child, depth + 1));
+                        }
+                    }
+                }
+                rows.push(row);
+            }
+
+            let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+            TableBuilder::new(ui)
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .column(Column::remainder().at_least(256.0))
+                .columns(Column::auto().at_least(80.0), 5)
+                .min_scrolled_height(0.0)
+                .header(20.0, |mut header| {
+                    for title in ["control group", "tasks", "CPU", "memory", "read", "written"] {
+                        header.col(|ui| {
+                            ui.strong(title);
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(text_height, rows.len(), |index, mut row| match rows[index] {
+                        Row::Cgroup(cgroup, depth) => {
+                            let rates = self.rates.get(&cgroup.path);
+                            row.col(|ui| {
+                                ui.add_space(depth as f32 * 16.0);
+                                let expandable = !cgroup.children.is_empty() || !cgroup.pids.is_empty();
+                                if expandable && depth > 0 {
+                                    let arrow = if self.expanded.contains(&cgroup.path) { "⏷" } else { "⏵" };
+                                    if ui.small_button(arrow).clicked() {
+                                        toggled = Some(cgroup.path.clone());
+                                    }
+                                }
+                                ui.label(cgroup.name()).on_hover_text(&cgroup.path);
+                            });
+                            row.col(|ui| {
+                                ui.label(cgroup.process_count().to_string());
+                            });
+                            row.col(|ui| {
+                                if let Some((cpu, _)) = rates {
+                                    ui.label(format!("{cpu:.1}%"));
+                                }
+                            });
+                            row.col(|ui| {
+                                if let Some(memory) = cgroup.memory {
+                                    ui.label(format!("{:.1} {}", memory as f64 / mib, mib_name));
+                                }
+                            });
+                            for index in 0..2 {
+                                row.col(|ui| {
+                                    if let Some((_, io)) = rates {
+                                        ui.label(format!("{:.1} {}/s", io[index] / kib, kib_name));
+                                    }
+                                });
+                            }
+                        }
+                        Row::Process(pid, depth) => {
+                            row.col(|ui| {
+                                ui.add_space(depth as f32 * 16.0);
+                                let name = system.process(pid).map_or("", |process| process.name());
+                                if ui
+                                    .link(format!("{name} ({pid})"))
+                                    .on_hover_text("Show in the process list")
+                                    .clicked()
+                                {
+                                    jump = Some(pid);
+                                }
+                            });
+                            row.col(|ui| {
+                                ui.label("1");
+                            });
+                            row.col(|ui| {
+                                if let Some(process) = system.process(pid) {
+                                    ui.label(format!("{:.1}%", process.cpu_usage()));
+                                }
+                            });
+                            row.col(|ui| {
+                                if let Some(process) = system.process(pid) {
+                                    ui.label(format!("{:.1} {}", process.memory() as f64 / mib, mib_name));
+                                }
+                            });
+                            row.col(|_| {});
+                            row.col(|_| {});
+                        }
+                    });
+                });
+        });
+        if let Some(path) = toggled {
+            if !self.expanded.remove(&path) {
+                self.expanded.insert(path);
+            }
+        }
+        jump
+    }
+}
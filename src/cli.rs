@@ -0,0 +1,89 @@
+use crate::{app::ViewKind, settings::REFRESH_INTERVAL_MS};
+
+pub const USAGE: &str = "Usage: task-manager [OPTIONS]
+
+Options:
+  --view <VIEW>       Open this view, such as processes, performance or graphs
+  --filter <SEARCH>   Search the process list, with a label search such as owner:root
+                      or with the name of the processes
+  --interval <MS>     Refresh everything every this many milliseconds, from 100 to 60000
+  --maximized         Start with the window maximized
+  --dump <FORMAT>     Print the processes and the usage of the system as json or csv,
+                      without opening a window
+  -h, --help          Print this help";
+
+/// How `--dump` prints the snapshot.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DumpFormat {
+    Json,
+    Csv,
+}
+
+/// Options given on the command line, which override the settings for this
+/// run only.
+#[derive(Default)]
+pub struct Args {
+    pub view: Option<ViewKind>,
+    pub filter: Option<String>,
+    pub interval: Option<u64>,
+    pub maximized: bool,
+    pub dump: Option<DumpFormat>,
+    pub help: bool,
+}
+
+/// Name of a view on the command line, such as `open-files`.
+fn view_name(kind: ViewKind) -> String {
+    kind.label().to_lowercase().replace(' ', "-")
+}
+
+impl Args {
+    /// Parses the arguments, without the name of the program. Values are
+    /// given either as the next argument or after an `=`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg, None),
+            };
+            let mut value = || inline.clone().or_else(|| args.next()).ok_or_else(|| format!("{flag} needs a value"));
+            match flag.as_str() {
+                "--view" => {
+                    let name = value()?;
+                    let kind = ViewKind::ALL.iter().find(|kind| view_name(**kind) == name.to_lowercase());
+                    parsed.view = Some(*kind.ok_or_else(|| {
+                        let names = ViewKind::ALL.iter().map(|kind| view_name(*kind)).collect::<Vec<_>>();
+                        format!("Unknown view {name}, expected one of: {}", names.join(", "))
+                    })?);
+                }
+                "--filter" => parsed.filter = Some(value()?),
+                "--interval" => {
+                    let interval = value()?;
+                    match interval.parse::<u64>() {
+                        Ok(ms) if REFRESH_INTERVAL_MS.contains(&ms) => parsed.interval = Some(ms),
+                        _ => {
+                            return Err(format!(
+                                "Invalid interval {interval}, expected {} to {} milliseconds",
+                                REFRESH_INTERVAL_MS.start(),
+                                REFRESH_INTERVAL_MS.end()
+                            ))
+                        }
+                    }
+                }
+                "--maximized" => parsed.maximized = true,
+                "--dump" => {
+                    let format = value()?;
+                    parsed.dump = Some(match format.to_lowercase().as_str() {
+                        "json" => DumpFormat::Json,
+                        "csv" => DumpFormat::Csv,
+                        _ => return Err(format!("Unknown format {format}, expected json or csv")),
+                    });
+                }
+                "-h" | "--help" => parsed.help = true,
+                _ => return Err(format!("Unknown argument {flag}")),
+            }
+        }
+        Ok(parsed)
+    }
+}
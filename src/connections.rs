@@ -0,0 +1,244 @@
+use std::{
+    collections::HashMap,
+    fs,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use egui_extras::{Column, TableBuilder};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+use crate::settings::Settings;
+
+/// An open socket, from `/proc/net`.
+pub struct Connection {
+    pub protocol: &'static str,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    pub state: &'static str,
+    /// Process owning the socket, if it is visible to the current user.
+    pub pid: Option<Pid>,
+}
+
+/// All the TCP and UDP sockets of the machine.
+pub fn connections() -> Vec<Connection> {
+    let owners = socket_owners();
+    let mut connections = vec![];
+    for protocol in ["tcp", "tcp6", "udp", "udp6"] {
+        let Ok(table) = fs::read_to_string(format!("/proc/net/{protocol}")) else {
+            continue;
+        };
+        for line in table.lines().skip(1) {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let (Some(local), Some(remote), Some(state), Some(inode)) = (
+                fields.get(1).and_then(|field| parse_address(field)),
+                fields.get(2).and_then(|field| parse_address(field)),
+                fields.get(3).and_then(|field| u8::from_str_radix(field, 16).ok()),
+                fields.get(9).and_then(|field| field.parse::<u64>().ok()),
+            ) else {
+                continue;
+            };
+            let tcp = protocol.starts_with("tcp");
+            connections.push(Connection {
+                protocol: if tcp { "TCP" } else { "UDP" },
+                local,
+                remote,
+                state: state_name(state, tcp),
+                pid: owners.get(&inode).copied(),
+            });
+        }
+    }
+    connections
+}
+
+/// Parses an address of `/proc/net`, such as `0100007F:0035` for
+/// 127.0.0.1:53. Addresses are made of 32 bit words in host byte order.
+fn parse_address(field: &str) -> Option<SocketAddr> {
+    let (ip, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let words = (0..ip.len() / 8)
+        .map(|index| u32::from_str_radix(&ip[index * 8..index * 8 + 8], 16).map(u32::from_be))
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    let ip = match words[..] {
+        [word] => IpAddr::V4(Ipv4Addr::from(word.to_be_bytes())),
+        [a, b, c, d] => {
+            let mut bytes = [0; 16];
+            for (chunk, word) in bytes.chunks_mut(4).zip([a, b, c, d]) {
+                chunk.copy_from_slice(&word.to_be_bytes());
+            }
+            IpAddr::V6(Ipv6Addr::from(bytes))
+        }
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+/// Name of a socket state, as found in `include/net/tcp_states.h`.
+fn state_name(state: u8, tcp: bool) -> &'static str {
+    match state {
+        0x01 => "ESTABLISHED",
+        0x02 => "SYN_SENT",
+        0x03 => "SYN_RECV",
+        0x04 => "FIN_WAIT1",
+        0x05 => "FIN_WAIT2",
+        0x06 => "TIME_WAIT",
+        // UDP sockets are "closed" until they are connected.
+        0x07 if !tcp => "UNCONN",
+        0x07 => "CLOSE",
+        0x08 => "CLOSE_WAIT",
+        0x09 => "LAST_ACK",
+        0x0A => "LISTEN",
+        0x0B => "CLOSING",
+        _ => "UNKNOWN",
+    }
+}
+
+/// The process owning each socket inode, found through the `socket:[inode]`
+/// links of the file descriptors of the processes.
+fn socket_owners() -> HashMap<u64, Pid> {
+    let mut owners = HashMap::new();
+    let Ok(processes) = fs::read_dir("/proc") else {
+        return owners;
+    };
+    for process in processes.flatten() {
+        let Some(pid) = process.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let inode = target
+                .to_str()
+                .and_then(|target| target.strip_prefix("socket:["))
+                .and_then(|target| target.strip_suffix(']'))
+                .and_then(|inode| inode.parse::<u64>().ok());
+            if let Some(inode) = inode {
+                owners.insert(inode, Pid::from_u32(pid));
+            }
+        }
+    }
+    owners
+}
+
+#[derive(Default)]
+pub struct ConnectionsState {
+    filter: String,
+    connections: Vec<Connection>,
+    /// Reading the sockets goes through all the file descriptors, so it is
+    /// only done at the refresh interval of the processes, apart from the
+    /// UI.
+    refreshed: Option<Instant>,
+    /// Whether a thread is reading them.
+    listing: bool,
+    /// Set by the thread reading them.
+    listed: Arc<Mutex<Option<Vec<Connection>>>>,
+}
+
+impl ConnectionsState {
+    /// Returns the process to show in the process list, if one was clicked.
+    pub fn connections_view(
+        &mut self,
+        ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        system: Arc<Mutex<System>>,
+        settings: Arc<Mutex<Settings>>,
+    ) -> Option<Pid> {
+        let interval = Duration::from_millis(settings.lock().unwrap().refresh_intervals.processes_ms);
+        if let Some(listed) = self.listed.lock().unwrap().take() {
+            self.connections = listed;
+            self.listing = false;
+        }
+        if !self.listing && self.refreshed.map_or(true, |time| time.elapsed() >= interval) {
+            self.listing = true;
+            self.refreshed = Some(Instant::now());
+            let listed = self.listed.clone();
+            let ctx = ctx.clone();
+            std::thread::spawn(move || {
+                *listed.lock().unwrap() = Some(connections());
+                ctx.request_repaint();
+            });
+        }
+        ctx.request_repaint_after(interval);
+
+        let system = system.lock().unwrap();
+        let mut jump = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Search:");
+                ui.text_edit_singleline(&mut self.filter)
+                    .on_hover_text("Matches the protocol, the addresses, the state and the process");
+            });
+            let filter = self.filter.to_lowercase();
+            let rows = self
+                .connections
+                .iter()
+                .map(|connection| {
+                    let process = connection
+                        .pid
+                        .and_then(|pid| system.process(pid))
+                        .map(|process| format!("{} ({})", process.name(), process.pid()));
+                    (connection, process)
+                })
+                .filter(|(connection, process)| {
+                    [
+                        connection.protocol.to_lowercase(),
+                        connection.local.to_string(),
+                        connection.remote.to_string(),
+                        connection.state.to_lowercase(),
+                        process.clone().unwrap_or_default().to_lowercase(),
+                    ]
+                    .iter()
+                    .any(|field| field.contains(&filter))
+                })
+                .collect::<Vec<_>>();
+
+            let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+            TableBuilder::new(ui)
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .column(Column::auto().at_least(48.0))
+                .column(Column::auto().at_least(160.0))
+                .column(Column::auto().at_least(160.0))
+                .column(Column::auto().at_least(96.0))
+                .column(Column::remainder())
+                .min_scrolled_height(0.0)
+                .header(20.0, |mut header| {
+                    for title in ["protocol", "local", "remote", "state", "process"] {
+                        header.col(|ui| {
+                            ui.strong(title);
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(text_height, rows.len(), |index, mut row| {
+                        let (connection, process) = &rows[index];
+                        row.col(|ui| {
+                            ui.label(connection.protocol);
+                        });
+                        row.col(|ui| {
+                            ui.label(connection.local.to_string());
+                        });
+                        row.col(|ui| {
+                            ui.label(connection.remote.to_string());
+                        });
+                        row.col(|ui| {
+                            ui.label(connection.state);
+                        });
+                        row.col(|ui| {
+                            if let Some(process) = process {
+                                if ui.link(process).on_hover_text("Show in the process list").clicked() {
+                                    jump = connection.pid;
+                                }
+                            }
+                        });
+                    });
+                });
+        });
+        jump
+    }
+}
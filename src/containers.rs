@@ -0,0 +1,328 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use egui_extras::{Column, TableBuilder};
+use serde::Deserialize;
+use sysinfo::{Pid, PidExt};
+
+use crate::settings::Settings;
+
+/// A running container, from the API of Docker or Podman.
+pub struct Container {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub status: String,
+    /// Socket of the engine running the container.
+    socket: PathBuf,
+    pub pids: Vec<Pid>,
+    /// Memory in use, in bytes.
+    pub memory: Option<u64>,
+    /// CPU time used since the container started, in microseconds.
+    cpu_usec: Option<u64>,
+}
+
+/// A container as listed by `GET /containers/json`, which Podman implements
+/// too.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListedContainer {
+    id: String,
+    names: Vec<String>,
+    image: String,
+    status: String,
+}
+
+/// The sockets of the Docker and Podman engines that exist, system wide and
+/// for the current user.
+fn sockets() -> Vec<PathBuf> {
+    let mut sockets = vec![PathBuf::from("/var/run/docker.sock"), PathBuf::from("/run/podman/podman.sock")];
+    if let Some(runtime) = env::var_os("XDG_RUNTIME_DIR") {
+        sockets.push(Path::new(&runtime).join("docker.sock"));
+        sockets.push(Path::new(&runtime).join("podman/podman.sock"));
+    }
+    sockets.retain(|socket| socket.exists());
+    sockets
+}
+
+/// Sends an HTTP request to an engine and returns the body of the response.
+/// HTTP/1.0 is used so that the response is neither chunked nor kept alive.
+fn request(socket: &Path, method: &str, path: &str) -> Result<String, String> {
+    let mut stream = UnixStream::connect(socket).map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(Duration::from_secs(30))).map_err(|e| e.to_string())?;
+    write!(stream, "{method} {path} HTTP/1.0\r\nHost: localhost\r\nContent-Length: 0\r\n\r\n").map_err(|e| e.to_string())?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).map_err(|e| e.to_string())?;
+    let (head, body) = response.split_once("\r\n\r\n").ok_or_else(|| "Invalid response".to_string())?;
+    let status = head.lines().next().unwrap_or_default();
+    if status.split_whitespace().nth(1).map_or(false, |code| code.starts_with('2')) {
+        Ok(body.to_string())
+    } else {
+        Err(format!("{status}: {}", body.trim()))
+    }
+}
+
+/// The running containers of all the engines, with the processes in each.
+/// Fails only if no engine could be reached.
+pub fn containers() -> Result<Vec<Container>, String> {
+    let cgroups = container_cgroups();
+    let mut containers = vec![];
+    let mut error = None;
+    for socket in sockets() {
+        let listed = request(&socket, "GET", "/containers/json")
+            .and_then(|body| serde_json::from_str::<Vec<ListedContainer>>(&body).map_err(|e| e.to_string()));
+        let listed = match listed {
+            Ok(listed) => listed,
+            Err(e) => {
+                error = Some(format!("{}: {e}", socket.display()));
+                continue;
+            }
+        };
+        for container in listed {
+            let (cgroup, pids) = cgroups
+                .iter()
+                .find(|(id, _)| **id == container.id)
+                .map(|(_, (cgroup, pids))| (Some(cgroup.clone()), pids.clone()))
+                .unwrap_or_default();
+            let cgroup_file = |file: &str| {
+                let path = cgroup.as_ref()?.join(file);
+                fs::read_to_string(path).ok()
+            };
+            containers.push(Container {
+                name: container.names.first().map_or(&container.id[..12], |name| name.trim_start_matches('/')).to_string(),
+                id: container.id,
+                image: container.image,
+                status: container.status,
+                socket: socket.clone(),
+                memory: cgroup_file("memory.current").and_then(|memory| memory.trim().parse().ok()),
+                cpu_usec: cgroup_file("cpu.stat").and_then(|stat| {
+                    stat.lines().find_map(|line| line.strip_prefix("usage_usec ")?.parse().ok())
+                }),
+                pids,
+            });
+        }
+    }
+    match error {
+        Some(error) if containers.is_empty() => Err(error),
+        _ => Ok(containers),
+    }
+}
+
+/// The control group and the processes of each container, by the ID found
+/// in the path of the group, such as `docker-<id>.scope` or
+/// `libpod-<id>.scope` with cgroups v2.
+fn container_cgroups() -> HashMap<String, (PathBuf, Vec<Pid>)> {
+    let mut cgroups: HashMap<String, (PathBuf, Vec<Pid>)> = HashMap::new();
+    let Ok(processes) = fs::read_dir("/proc") else {
+        return cgroups;
+    };
+    for process in processes.flatten() {
+        let Some(pid) = process.file_name().to_str().and_then(|pid| pid.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(cgroup) = fs::read_to_string(process.path().join("cgroup")) else {
+            continue;
+        };
+        let Some(path) = cgroup.lines().find_map(|line| line.strip_prefix("0::")) else {
+            continue;
+        };
+        let id = path.split('/').find_map(|segment| {
+            let id = segment.trim_end_matches(".scope");
+            let id = id.rsplit(['-', ':']).next()?;
+            (id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit())).then(|| id.to_string())
+        });
+        if let Some(id) = id {
+            let entry = cgroups
+                .entry(id)
+                .or_insert_with(|| (Path::new("/sys/fs/cgroup").join(path.trim_start_matches('/')), vec![]));
+            entry.1.push(Pid::from_u32(pid));
+        }
+    }
+    cgroups
+}
+
+#[derive(Clone, Copy)]
+enum ContainerAction {
+    Stop,
+    Restart,
+}
+
+/// The containers listed by a thread, with when they were.
+type Listing = (Instant, Result<Vec<Container>, String>);
+
+#[derive(Default)]
+pub struct ContainersState {
+    containers: Vec<Container>,
+    /// CPU usage of each container since the previous refresh, in percent
+    /// of one core.
+    cpu: HashMap<String, f64>,
+    error: Option<String>,
+    refreshed: Option<Instant>,
+    /// When the shown containers were listed, to compute their CPU usage.
+    listed_at: Option<Instant>,
+    /// Whether a thread is listing the containers, as the engines can be
+    /// slow to answer.
+    listing: bool,
+    /// Set by the thread listing them.
+    listed: Arc<Mutex<Option<Listing>>>,
+    /// Outcome of the last action, set by the thread running it.
+    status: Arc<Mutex<Option<String>>>,
+}
+
+impl ContainersState {
+    fn update(&mut self, (time, listed): Listing) {
+        match listed {
+            Ok(containers) => {
+                let elapsed = self.listed_at.map(|previous| time.duration_since(previous).as_micros() as f64);
+                self.cpu.clear();
+                for container in &containers {
+                    let previous = self
+                        .containers
+                        .iter()
+                        .find(|previous| previous.id == container.id)
+                        .and_then(|previous| previous.cpu_usec);
+                    if let (Some(usec), Some(previous), Some(elapsed)) = (container.cpu_usec, previous, elapsed) {
+                        self.cpu
+                            .insert(container.id.clone(), usec.saturating_sub(previous) as f64 / elapsed * 100.0);
+                    }
+                }
+                self.containers = containers;
+                self.listed_at = Some(time);
+                self.error = None;
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    /// Lists the running containers. Returns the name and the processes of
+    /// the container to show in the process list, if one was clicked.
+    pub fn containers_view(
+        &mut self,
+        ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        settings: Arc<Mutex<Settings>>,
+    ) -> Option<(String, Vec<Pid>)> {
+        let (interval, byte_units) = {
+            let settings = settings.lock().unwrap();
+            (Duration::from_millis(settings.refresh_intervals.processes_ms), settings.byte_units)
+        };
+        let listing = self.listed.lock().unwrap().take();
+        if let Some(listing) = listing {
+            self.listing = false;
+            self.update(listing);
+        }
+        if !self.listing && self.refreshed.map_or(true, |time| time.elapsed() >= interval) {
+            self.listing = true;
+            self.refreshed = Some(Instant::now());
+            let listed = self.listed.clone();
+            let ctx = ctx.clone();
+            std::thread::spawn(move || {
+                let containers = containers();
+                *listed.lock().unwrap() = Some((Instant::now(), containers));
+                ctx.request_repaint();
+            });
+        }
+        ctx.request_repaint_after(interval);
+        let (mib, mib_name) = byte_units.unit(2);
+
+        let mut jump = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if let Some(error) = &self.error {
+                ui.label(format!("Could not list the containers: {error}"));
+            } else if self.containers.is_empty() {
+                ui.label("No running Docker or Podman containers");
+            }
+            if let Some(status) = &*self.status.lock().unwrap() {
+                ui.label(status);
+            }
+            let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+            let mut action = None;
+            TableBuilder::new(ui)
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .column(Column::auto().at_least(128.0))
+                .column(Column::auto().at_least(192.0))
+                .column(Column::auto().at_least(128.0))
+                .columns(Column::auto().at_least(64.0), 3)
+                .column(Column::remainder())
+                .min_scrolled_height(0.0)
+                .header(20.0, |mut header| {
+                    for title in ["name", "image", "status", "CPU", "memory", "processes", "actions"] {
+                        header.col(|ui| {
+                            ui.strong(title);
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(text_height, self.containers.len(), |index, mut row| {
+                        let container = &self.containers[index];
+                        row.col(|ui| {
+                            ui.label(&container.name);
+                        });
+                        row.col(|ui| {
+                            ui.label(&container.image);
+                        });
+                        row.col(|ui| {
+                            ui.label(&container.status);
+                        });
+                        row.col(|ui| {
+                            if let Some(cpu) = self.cpu.get(&container.id) {
+                                ui.label(format!("{cpu:.1}%"));
+                            }
+                        });
+                        row.col(|ui| {
+                            if let Some(memory) = container.memory {
+                                ui.label(format!("{:.0} {}", memory as f64 / mib, mib_name));
+                            }
+                        });
+                        row.col(|ui| {
+                            if ui
+                                .link(container.pids.len().to_string())
+                                .on_hover_text("Show in the process list")
+                                .clicked()
+                            {
+                                jump = Some((container.name.clone(), container.pids.clone()));
+                            }
+                        });
+                        row.col(|ui| {
+                            if ui.small_button("Stop").clicked() {
+                                action = Some((index, ContainerAction::Stop));
+                            }
+                            if ui.small_button("Restart").clicked() {
+                                action = Some((index, ContainerAction::Restart));
+                            }
+                        });
+                    });
+                });
+
+            // Stopping waits for the container to exit, so it is done apart
+            // from the UI.
+            if let Some((index, action)) = action {
+                let container = &self.containers[index];
+                let (socket, id, name) = (container.socket.clone(), container.id.clone(), container.name.clone());
+                let status = self.status.clone();
+                let ctx = ctx.clone();
+                let verb = match action {
+                    ContainerAction::Stop => "stop",
+                    ContainerAction::Restart => "restart",
+                };
+                *status.lock().unwrap() = Some(format!("Sent {verb} to {name}..."));
+                std::thread::spawn(move || {
+                    let message = match request(&socket, "POST", &format!("/containers/{id}/{verb}")) {
+                        Ok(_) => format!("{name}: {verb} done"),
+                        Err(error) => format!("{name}: {error}"),
+                    };
+                    *status.lock().unwrap() = Some(message);
+                    ctx.request_repaint();
+                });
+            }
+        });
+        jump
+    }
+}
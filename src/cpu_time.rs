@@ -0,0 +1,37 @@
+use std::fs;
+
+/// Time spent by all the CPUs since boot, in clock ticks, from the first
+/// line of `/proc/stat`.
+#[derive(Clone, Copy)]
+pub struct CpuTimes {
+    /// User time, including niced processes.
+    pub user: u64,
+    /// Kernel time, including interrupts.
+    pub system: u64,
+    pub iowait: u64,
+    /// Time stolen by the hypervisor for other virtual machines.
+    pub steal: u64,
+    pub total: u64,
+}
+
+pub fn cpu_times() -> Option<CpuTimes> {
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+    let fields = stat
+        .lines()
+        .next()?
+        .split_whitespace()
+        .skip(1)
+        .take(8)
+        .map(|field| field.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let [user, nice, system, idle, iowait, irq, softirq, steal] = fields[..] else {
+        return None;
+    };
+    Some(CpuTimes {
+        user: user + nice,
+        system: system + irq + softirq,
+        iowait,
+        steal,
+        total: user + nice + system + idle + iowait + irq + softirq + steal,
+    })
+}
@@ -0,0 +1,98 @@
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use egui::ProgressBar;
+use egui_extras::{Column, TableBuilder};
+use sysinfo::{DiskExt, System, SystemExt};
+
+use crate::settings::Settings;
+
+/// Bytes read and written since boot by each block device, from
+/// `/proc/diskstats`. Partitions are left out as their device already
+/// accounts for them, and so are loop and RAM devices.
+pub fn disk_io() -> Vec<(String, [u64; 2])> {
+    let Ok(stats) = fs::read_to_string("/proc/diskstats") else {
+        return vec![];
+    };
+    stats
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let name = *fields.get(2)?;
+            if name.starts_with("loop") || name.starts_with("ram") || !Path::new("/sys/block").join(name).exists() {
+                return None;
+            }
+            // Sectors are always 512 bytes in this file, whatever the device.
+            let read = fields.get(5)?.parse::<u64>().ok()? * 512;
+            let written = fields.get(9)?.parse::<u64>().ok()? * 512;
+            Some((name.to_string(), [read, written]))
+        })
+        .collect()
+}
+
+/// Lists the mounted filesystems with how full they are, colored past the
+/// disk space thresholds.
+pub fn disks_view(
+    ctx: &egui::Context,
+    _frame: &mut eframe::Frame,
+    system: Arc<Mutex<System>>,
+    settings: Arc<Mutex<Settings>>,
+) {
+    let (byte_units, threshold) = {
+        let settings = settings.lock().unwrap();
+        (settings.byte_units, settings.thresholds.disk)
+    };
+    let (gib, gib_name) = byte_units.unit(3);
+    let system = system.lock().unwrap();
+    egui::CentralPanel::default().show(ctx, |ui| {
+        let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+        TableBuilder::new(ui)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::auto().at_least(128.0))
+            .column(Column::auto().at_least(128.0))
+            .column(Column::auto().at_least(64.0))
+            .column(Column::auto().at_least(160.0))
+            .column(Column::remainder().at_least(128.0))
+            .min_scrolled_height(0.0)
+            .header(20.0, |mut header| {
+                for title in ["mount point", "device", "filesystem", "used", "usage"] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(text_height, system.disks().len(), |index, mut row| {
+                    let disk = &system.disks()[index];
+                    let total = disk.total_space() as f64;
+                    let used = total - disk.available_space() as f64;
+                    row.col(|ui| {
+                        ui.label(disk.mount_point().display().to_string());
+                        if disk.is_removable() {
+                            ui.label("⏏").on_hover_text("Removable");
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.label(disk.name().to_string_lossy());
+                    });
+                    row.col(|ui| {
+                        ui.label(String::from_utf8_lossy(disk.file_system()));
+                    });
+                    row.col(|ui| {
+                        ui.label(format!("{:.1} / {:.1} {}", used / gib, total / gib, gib_name));
+                    });
+                    row.col(|ui| {
+                        let usage = if total > 0.0 { used / total } else { 0.0 } as f32;
+                        let mut bar = ProgressBar::new(usage).show_percentage();
+                        if let Some(level) = threshold.level(usage * 100.0) {
+                            bar = bar.fill(level.color());
+                        }
+                        ui.add(bar);
+                    });
+                });
+            });
+    });
+}
@@ -0,0 +1,202 @@
+use std::{fmt::Write, path::Path};
+
+use egui::Color32;
+use resvg::{tiny_skia, usvg};
+
+const WIDTH: f64 = 900.0;
+const HEIGHT: f64 = 450.0;
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 70.0;
+const TICKS: usize = 5;
+
+/// A graph as displayed in the graphs view, ready to be written to a file.
+pub struct Chart<'a> {
+    pub title: &'a str,
+    /// Visible area, as `[min_x, min_y]` and `[max_x, max_y]`.
+    pub bounds: ([f64;2], [f64;2]),
+    pub lines: &'a [(String, Color32, Vec<[f64;2]>)],
+    /// Whether the area between consecutive lines is filled.
+    pub stacked: bool,
+    pub thresholds: &'a [(String, f64)],
+    /// Vertical markers, as x and label.
+    pub markers: &'a [(f64, String)],
+    pub x_label: &'a dyn Fn(f64) -> String,
+    pub y_label: &'a dyn Fn(f64) -> String,
+}
+
+impl Chart<'_> {
+    /// Writes the chart to `path`, as a PNG if the extension is `png` and as
+    /// an SVG otherwise.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let svg = self.svg();
+        let is_png = path
+            .extension()
+            .map_or(false, |extension| extension.eq_ignore_ascii_case("png"));
+        if !is_png {
+            return std::fs::write(path, svg).map_err(|e| e.to_string());
+        }
+
+        let mut options = usvg::Options::default();
+        let fonts = options.fontdb_mut();
+        fonts.load_system_fonts();
+        // Use the font bundled with egui, which unlike system fonts is
+        // always available.
+        if let Some(font) = egui::FontDefinitions::default().font_data.get("Ubuntu-Light") {
+            fonts.load_font_data(font.font.to_vec());
+            if let Some((family, _)) = fonts.faces().last().and_then(|face| face.families.first()).cloned() {
+                fonts.set_sans_serif_family(family);
+            }
+        }
+        let tree = usvg::Tree::from_str(&svg, &options).map_err(|e| e.to_string())?;
+        // Rendered at twice the size so that it stays sharp on HiDPI screens.
+        let size = tree.size().to_int_size();
+        let mut pixmap = tiny_skia::Pixmap::new(size.width() * 2, size.height() * 2)
+            .ok_or_else(|| "Invalid image size".to_string())?;
+        resvg::render(&tree, tiny_skia::Transform::from_scale(2.0, 2.0), &mut pixmap.as_mut());
+        pixmap.save_png(path).map_err(|e| e.to_string())
+    }
+
+    pub fn svg(&self) -> String {
+        let ([min_x, min_y], [max_x, max_y]) = self.bounds;
+        let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+        let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+        let to_x = |x: f64| MARGIN_LEFT + (x - min_x) / (max_x - min_x) * plot_width;
+        let to_y = |y: f64| MARGIN_TOP + (max_y - y) / (max_y - min_y) * plot_height;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="12">"#
+        );
+        let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+        let _ = writeln!(
+            svg,
+            r#"<clipPath id="plot"><rect x="{MARGIN_LEFT}" y="{MARGIN_TOP}" width="{plot_width}" height="{plot_height}"/></clipPath>"#
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="24" text-anchor="middle" font-size="16" font-weight="bold">{}</text>"#,
+            WIDTH / 2.0,
+            escape(self.title)
+        );
+
+        for tick in 0..=TICKS {
+            let fraction = tick as f64 / TICKS as f64;
+            let x = min_x + (max_x - min_x) * fraction;
+            let y = min_y + (max_y - min_y) * fraction;
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{0}" y1="{1}" x2="{0}" y2="{2}" stroke="#ddd"/><text x="{0}" y="{3}" text-anchor="middle">{4}</text>"##,
+                to_x(x),
+                MARGIN_TOP,
+                MARGIN_TOP + plot_height,
+                MARGIN_TOP + plot_height + 16.0,
+                escape(&(self.x_label)(x))
+            );
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{0}" y1="{1}" x2="{2}" y2="{1}" stroke="#ddd"/><text x="{3}" y="{4}" text-anchor="end">{5}</text>"##,
+                MARGIN_LEFT,
+                to_y(y),
+                MARGIN_LEFT + plot_width,
+                MARGIN_LEFT - 6.0,
+                to_y(y) + 4.0,
+                escape(&(self.y_label)(y))
+            );
+        }
+
+        let _ = writeln!(svg, r#"<g clip-path="url(#plot)" fill="none" stroke-width="1.5">"#);
+        let mut lower: Option<&Vec<[f64;2]>> = None;
+        for (_, color, points) in self.lines {
+            if points.is_empty() {
+                continue;
+            }
+            let path = points
+                .iter()
+                .map(|[x, y]| format!("{:.2},{:.2}", to_x(*x), to_y(*y)))
+                .collect::<Vec<_>>()
+                .join(" L");
+            if self.stacked {
+                let base = match lower {
+                    Some(lower) => lower
+                        .iter()
+                        .rev()
+                        .map(|[x, y]| format!("{:.2},{:.2}", to_x(*x), to_y(*y)))
+                        .collect::<Vec<_>>()
+                        .join(" L"),
+                    None => format!(
+                        "{:.2},{:.2} L{:.2},{:.2}",
+                        to_x(points[points.len() - 1][0]),
+                        to_y(0.0),
+                        to_x(points[0][0]),
+                        to_y(0.0)
+                    ),
+                };
+                let _ = writeln!(
+                    svg,
+                    r#"<path d="M{} L{} Z" fill="{}" fill-opacity="0.5" stroke="none"/>"#,
+                    path,
+                    base,
+                    hex(*color)
+                );
+                lower = Some(points);
+            }
+            let _ = writeln!(svg, r#"<path d="M{}" stroke="{}"/>"#, path, hex(*color));
+        }
+        for (_, y) in self.thresholds {
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{}" y1="{2}" x2="{}" y2="{2}" stroke="red" stroke-dasharray="6 6"/>"#,
+                MARGIN_LEFT,
+                MARGIN_LEFT + plot_width,
+                to_y(*y)
+            );
+        }
+        for (x, label) in self.markers {
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{0}" y1="{1}" x2="{0}" y2="{2}" stroke="goldenrod" stroke-dasharray="3 3"/><text x="{3}" y="{4}" fill="goldenrod" stroke="none" font-size="10">{5}</text>"#,
+                to_x(*x),
+                MARGIN_TOP,
+                MARGIN_TOP + plot_height,
+                to_x(*x) + 3.0,
+                MARGIN_TOP + 12.0,
+                escape(label)
+            );
+        }
+        let _ = writeln!(svg, "</g>");
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{MARGIN_LEFT}" y="{MARGIN_TOP}" width="{plot_width}" height="{plot_height}" fill="none" stroke="black"/>"#
+        );
+
+        let mut legend_x = MARGIN_LEFT;
+        let legend_y = HEIGHT - 20.0;
+        for (name, color, _) in self.lines {
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="10" height="10" fill="{}"/><text x="{}" y="{}">{}</text>"#,
+                legend_x,
+                legend_y - 9.0,
+                hex(*color),
+                legend_x + 14.0,
+                legend_y,
+                escape(name)
+            );
+            legend_x += 24.0 + name.chars().count() as f64 * 7.0;
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+fn hex(color: Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,19 @@
+use chrono::{Local, TimeZone};
+
+/// Formats a UNIX timestamp as a local date and time.
+pub fn format_time(timestamp: i64) -> String {
+    format_local(timestamp, "%Y-%m-%d %H:%M:%S")
+}
+
+/// Formats a UNIX timestamp as a local wall-clock time, for the time axes.
+pub fn format_clock(timestamp: i64) -> String {
+    format_local(timestamp, "%H:%M:%S")
+}
+
+fn format_local(timestamp: i64, format: &str) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format(format).to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,374 @@
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use egui::ProgressBar;
+use egui_extras::{Column, TableBuilder};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+use crate::settings::Settings;
+
+/// A snapshot of one GPU.
+pub struct GpuInfo {
+    pub name: String,
+    /// Utilization, in percent.
+    pub utilization: f32,
+    /// VRAM in use, in bytes.
+    pub memory_used: u64,
+    /// Total VRAM, in bytes.
+    pub memory_total: u64,
+    /// Temperature, in degrees Celsius.
+    pub temperature: Option<f32>,
+    /// Power draw, in watts.
+    pub power: Option<f32>,
+}
+
+/// A process using a GPU.
+pub struct GpuProcess {
+    pub pid: u32,
+    /// VRAM used by the process, in bytes, if the driver reports it.
+    pub memory: Option<u64>,
+}
+
+/// A GPU with the processes using it.
+pub type GpuUsage = (GpuInfo, Vec<GpuProcess>);
+
+/// Reads GPU statistics. NVIDIA GPUs are read through NVML when the `nvml`
+/// feature is enabled, AMD GPUs through the amdgpu sysfs interface.
+pub struct Gpus {
+    #[cfg(feature = "nvml")]
+    nvml: Option<nvml_wrapper::Nvml>,
+}
+
+#[cfg_attr(not(feature = "nvml"), allow(clippy::derivable_impls))]
+impl Default for Gpus {
+    fn default() -> Self {
+        Self {
+            #[cfg(feature = "nvml")]
+            nvml: nvml_wrapper::Nvml::init().ok(),
+        }
+    }
+}
+
+impl Gpus {
+    pub fn gpus(&self) -> Vec<GpuInfo> {
+        self.read(false).into_iter().map(|(gpu, _)| gpu).collect()
+    }
+
+    /// Each GPU with the processes using it, read together so that a GPU
+    /// failing to read cannot shift the processes onto another one.
+    pub fn gpus_with_processes(&self) -> Vec<GpuUsage> {
+        self.read(true)
+    }
+
+    fn read(&self, processes: bool) -> Vec<GpuUsage> {
+        let mut gpus = self.nvml_gpus(processes);
+        gpus.extend(amdgpu_gpus(processes));
+        gpus
+    }
+
+    #[cfg(feature = "nvml")]
+    fn nvml_gpus(&self, processes: bool) -> Vec<GpuUsage> {
+        let Some(nvml) = &self.nvml else {
+            return vec![];
+        };
+        let count = nvml.device_count().unwrap_or(0);
+        (0..count)
+            .filter_map(|index| nvml.device_by_index(index).ok())
+            .filter_map(|device| {
+                let memory = device.memory_info().ok()?;
+                let gpu = GpuInfo {
+                    name: device.name().unwrap_or_else(|_| "NVIDIA GPU".to_string()),
+                    utilization: device.utilization_rates().ok()?.gpu as f32,
+                    memory_used: memory.used,
+                    memory_total: memory.total,
+                    temperature: device
+                        .temperature(nvml_wrapper::enum_wrappers::device::TemperatureSensor::Gpu)
+                        .ok()
+                        .map(|temperature| temperature as f32),
+                    power: device.power_usage().ok().map(|milliwatts| milliwatts as f32 / 1000.0),
+                };
+                Some((gpu, if processes { nvml_processes(&device) } else { vec![] }))
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "nvml"))]
+    fn nvml_gpus(&self, _processes: bool) -> Vec<GpuUsage> {
+        vec![]
+    }
+}
+
+#[cfg(feature = "nvml")]
+fn nvml_processes(device: &nvml_wrapper::Device<'_>) -> Vec<GpuProcess> {
+    let mut processes = device.running_graphics_processes().unwrap_or_default();
+    processes.extend(device.running_compute_processes().unwrap_or_default());
+    let mut processes = processes
+        .into_iter()
+        .map(|process| GpuProcess {
+            pid: process.pid,
+            memory: match process.used_gpu_memory {
+                nvml_wrapper::enums::device::UsedGpuMemory::Used(bytes) => Some(bytes),
+                nvml_wrapper::enums::device::UsedGpuMemory::Unavailable => None,
+            },
+        })
+        .collect::<Vec<_>>();
+    processes.sort_by_key(|process| process.pid);
+    processes.dedup_by_key(|process| process.pid);
+    processes
+}
+
+/// The amdgpu cards exposed in `/sys/class/drm`, such as `card0`.
+fn amdgpu_cards() -> Vec<String> {
+    let Ok(entries) = fs::read_dir("/sys/class/drm") else {
+        return vec![];
+    };
+    let mut cards = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with("card") && !name.contains('-'))
+        .filter(|card| {
+            let device = Path::new("/sys/class/drm").join(card).join("device");
+            ["gpu_busy_percent", "mem_info_vram_used", "mem_info_vram_total"]
+                .iter()
+                .all(|file| device.join(file).exists())
+        })
+        .collect::<Vec<_>>();
+    cards.sort();
+    cards
+}
+
+fn amdgpu_gpus(processes: bool) -> Vec<GpuUsage> {
+    amdgpu_cards()
+        .into_iter()
+        .filter_map(|card| {
+            let device = Path::new("/sys/class/drm").join(&card).join("device");
+            // The hwmon directory of the card reports in millidegrees and
+            // microwatts.
+            let hwmon = fs::read_dir(device.join("hwmon"))
+                .ok()
+                .and_then(|mut entries| entries.next())
+                .and_then(|entry| entry.ok())
+                .map(|entry| entry.path());
+            let temperature = hwmon
+                .as_ref()
+                .and_then(|hwmon| read_number(&hwmon.join("temp1_input")))
+                .map(|millidegrees| millidegrees as f32 / 1000.0);
+            let power = hwmon
+                .as_ref()
+                .and_then(|hwmon| {
+                    read_number(&hwmon.join("power1_average")).or_else(|| read_number(&hwmon.join("power1_input")))
+                })
+                .map(|microwatts| microwatts as f32 / 1_000_000.0);
+            let gpu = GpuInfo {
+                utilization: read_number(&device.join("gpu_busy_percent"))? as f32,
+                memory_used: read_number(&device.join("mem_info_vram_used"))?,
+                memory_total: read_number(&device.join("mem_info_vram_total"))?,
+                temperature,
+                power,
+                name: fs::read_to_string(device.join("product_name"))
+                    .map(|name| name.trim().to_string())
+                    .ok()
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or_else(|| format!("AMD GPU ({})", card)),
+            };
+            Some((gpu, if processes { amdgpu_processes(&card) } else { vec![] }))
+        })
+        .collect()
+}
+
+/// The processes with the card open, from the DRM usage stats of their
+/// file descriptors in `/proc/<pid>/fdinfo`.
+fn amdgpu_processes(card: &str) -> Vec<GpuProcess> {
+    let Some(slot) = fs::read_link(Path::new("/sys/class/drm").join(card).join("device"))
+        .ok()
+        .and_then(|device| Some(device.file_name()?.to_string_lossy().to_string()))
+    else {
+        return vec![];
+    };
+    let Ok(pids) = fs::read_dir("/proc") else {
+        return vec![];
+    };
+    let mut processes = vec![];
+    for pid in pids.flatten() {
+        let Some(pid_number) = pid.file_name().to_str().and_then(|pid| pid.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(pid.path().join("fdinfo")) else {
+            continue;
+        };
+        // A client can be open through several file descriptors.
+        let mut clients = vec![];
+        let mut memory = 0;
+        for fd in fds.flatten() {
+            let Ok(info) = fs::read_to_string(fd.path()) else {
+                continue;
+            };
+            let value = |key: &str| {
+                info.lines()
+                    .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+                    .map(|value| value.trim().to_string())
+            };
+            if value("drm-pdev").as_deref() != Some(slot.as_str()) {
+                continue;
+            }
+            let client = value("drm-client-id");
+            if clients.contains(&client) {
+                continue;
+            }
+            clients.push(client);
+            memory += value("drm-memory-vram")
+                .and_then(|vram| vram.strip_suffix(" KiB")?.parse::<u64>().ok())
+                .unwrap_or(0)
+                * 1024;
+        }
+        if !clients.is_empty() {
+            processes.push(GpuProcess {
+                pid: pid_number,
+                memory: Some(memory),
+            });
+        }
+    }
+    processes
+}
+
+fn read_number(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[derive(Default)]
+pub struct GpuState {
+    gpus: Vec<GpuUsage>,
+    /// Listing the processes goes through all the file descriptors for
+    /// amdgpu, so it is only done at the refresh interval of the processes,
+    /// apart from the UI.
+    refreshed: Option<Instant>,
+    /// Whether a thread is listing them.
+    listing: bool,
+    /// Set by the thread listing them.
+    listed: Arc<Mutex<Option<Vec<GpuUsage>>>>,
+}
+
+impl GpuState {
+    /// Shows the usage of each GPU and the processes using it. Returns the
+    /// process to show in the process list, if one was clicked.
+    pub fn gpu_view(
+        &mut self,
+        ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        gpus: Arc<Gpus>,
+        system: Arc<Mutex<System>>,
+        settings: Arc<Mutex<Settings>>,
+    ) -> Option<Pid> {
+        let (interval, byte_units, temperature_unit) = {
+            let settings = settings.lock().unwrap();
+            (
+                Duration::from_millis(settings.refresh_intervals.processes_ms),
+                settings.byte_units,
+                settings.temperature_unit,
+            )
+        };
+        if let Some(listed) = self.listed.lock().unwrap().take() {
+            self.gpus = listed;
+            self.listing = false;
+        }
+        if !self.listing && self.refreshed.map_or(true, |time| time.elapsed() >= interval) {
+            self.listing = true;
+            self.refreshed = Some(Instant::now());
+            let listed = self.listed.clone();
+            let ctx = ctx.clone();
+            thread::spawn(move || {
+                *listed.lock().unwrap() = Some(gpus.gpus_with_processes());
+                ctx.request_repaint();
+            });
+        }
+        ctx.request_repaint_after(interval);
+        let (mib, mib_name) = byte_units.unit(2);
+
+        let system = system.lock().unwrap();
+        let mut jump = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if self.gpus.is_empty() {
+                ui.label("No GPU found");
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (index, (gpu, processes)) in self.gpus.iter().enumerate() {
+                    ui.heading(&gpu.name);
+                    egui::Grid::new(("gpu", index)).num_columns(2).show(ui, |ui| {
+                        ui.label("Utilization");
+                        ui.add(ProgressBar::new(gpu.utilization / 100.0).show_percentage());
+                        ui.end_row();
+                        ui.label("VRAM");
+                        ui.add(
+                            ProgressBar::new(gpu.memory_used as f32 / gpu.memory_total.max(1) as f32).text(format!(
+                                "{:.0} / {:.0} {}",
+                                gpu.memory_used as f64 / mib,
+                                gpu.memory_total as f64 / mib,
+                                mib_name
+                            )),
+                        );
+                        ui.end_row();
+                        if let Some(temperature) = gpu.temperature {
+                            ui.label("Temperature");
+                            ui.label(format!(
+                                "{:.0} {}",
+                                temperature_unit.convert(temperature as f64),
+                                temperature_unit.symbol()
+                            ));
+                            ui.end_row();
+                        }
+                        if let Some(power) = gpu.power {
+                            ui.label("Power draw");
+                            ui.label(format!("{power:.1} W"));
+                            ui.end_row();
+                        }
+                    });
+
+                    ui.push_id(("gpu_processes", index), |ui| {
+                        let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+                        TableBuilder::new(ui)
+                            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                            .column(Column::auto().at_least(64.0))
+                            .column(Column::auto().at_least(192.0))
+                            .column(Column::remainder())
+                            .vscroll(false)
+                            .header(20.0, |mut header| {
+                                for title in ["pid", "name", "VRAM"] {
+                                    header.col(|ui| {
+                                        ui.strong(title);
+                                    });
+                                }
+                            })
+                            .body(|body| {
+                                body.rows(text_height, processes.len(), |row_index, mut row| {
+                                    let process = &processes[row_index];
+                                    let pid = Pid::from_u32(process.pid);
+                                    row.col(|ui| {
+                                        ui.label(process.pid.to_string());
+                                    });
+                                    row.col(|ui| {
+                                        if let Some(name) = system.process(pid).map(|process| process.name()) {
+                                            if ui.link(name).on_hover_text("Show in the process list").clicked() {
+                                                jump = Some(pid);
+                                            }
+                                        }
+                                    });
+                                    row.col(|ui| {
+                                        if let Some(memory) = process.memory {
+                                            ui.label(format!("{:.0} {}", memory as f64 / mib, mib_name));
+                                        }
+                                    });
+                                });
+                            });
+                    });
+                    ui.separator();
+                }
+            });
+        });
+        jump
+    }
+}
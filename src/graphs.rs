@@ -1,73 +1,838 @@
-use std::{sync::{Arc, Mutex}, thread::JoinHandle};
+use std::{collections::HashSet, path::Path, sync::{Arc, Mutex}};
 
-use egui::plot::{Line, Plot, PlotBounds};
-use sysinfo::{System, SystemExt, CpuExt};
+use egui::{
+    ecolor::Hsva,
+    plot::{HLine, Line, LineStyle, LinkedAxisGroup, Plot, PlotBounds, PlotPoint, Polygon, Text, VLine},
+    Align2, Color32, RichText, Stroke,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{export::Chart, format::format_clock, metrics::History, series::{self, Series}, settings::Settings};
+
+const PLOT_HEIGHT: f32 = 200.0;
 
 pub struct GraphsState {
-    points: Arc<Mutex<Vec<[f64;2]>>>,
-    thread: Option<JoinHandle<()>>,
-    secs: Arc<Mutex<usize>>,
-    plot_clicked: bool,
+    /// Whether the plots scroll with the latest samples. Panning or zooming
+    /// a plot turns it off until the view is reset.
+    follow_live: bool,
+    /// Seconds shown while following the live data, never more than the
+    /// retention.
+    window_secs: f64,
+    /// End of the plotted window while paused. Sampling goes on, so nothing
+    /// is lost when resuming.
+    paused_at: Option<f64>,
+    cpu_mode: CpuMode,
+    /// Number of samples averaged in the smoothed CPU line, 0 to disable it.
+    smoothing: usize,
+    time_axis: LinkedAxisGroup,
+    /// Series toggled off in the legend, as (graph title, series name).
+    hidden: HashSet<(String, String)>,
+    /// Titles of the graphs shown with a logarithmic y axis.
+    log_scale: HashSet<&'static str>,
+    export_path: String,
+    /// Result of the last export, shown in the toolbar.
+    export_status: Option<String>,
+    /// Graph opened from the performance view, shown even if it is not in
+    /// the layout.
+    focus: Option<GraphKind>,
+    /// Whether the focused graph is still to be scrolled to.
+    scroll_to_focus: bool,
 }
 
 impl Default for GraphsState {
     fn default() -> Self {
         Self {
-            points: Arc::new(Mutex::new(Vec::default())),
-            thread: None,
-            secs: Default::default(),
-            plot_clicked: false,
+            follow_live: true,
+            window_secs: 60.0,
+            paused_at: None,
+            cpu_mode: CpuMode::Total,
+            smoothing: 0,
+            time_axis: LinkedAxisGroup::x(),
+            hidden: HashSet::new(),
+            log_scale: HashSet::new(),
+            export_path: "graph.png".to_string(),
+            export_status: None,
+            focus: None,
+            scroll_to_focus: false,
+        }
+    }
+}
+
+/// The time windows that can be shown, in seconds.
+const WINDOWS: [(f64, &str); 4] = [
+    (60.0, "1 minute"),
+    (5.0 * 60.0, "5 minutes"),
+    (60.0 * 60.0, "1 hour"),
+    (24.0 * 60.0 * 60.0, "24 hours"),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CpuMode {
+    Total,
+    PerCore,
+    Stacked,
+}
+
+impl CpuMode {
+    const ALL: [CpuMode; 3] = [CpuMode::Total, CpuMode::PerCore, CpuMode::Stacked];
+
+    fn label(&self) -> &'static str {
+        match self {
+            CpuMode::Total => "Total CPU",
+            CpuMode::PerCore => "Per-core CPU",
+            CpuMode::Stacked => "Stacked cores",
+        }
+    }
+}
+
+/// The graphs that can be shown in the graphs view.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GraphKind {
+    Cpu,
+    CpuTime,
+    Frequency,
+    Gpu,
+    Vram,
+    LoadAverage,
+    Memory,
+    Swap,
+    Network,
+    DiskIo,
+    Temperatures,
+    ProcessCount,
+    ProcessCpu,
+    ProcessMemory,
+}
+
+impl GraphKind {
+    pub const ALL: [GraphKind; 14] = [
+        GraphKind::Cpu,
+        GraphKind::CpuTime,
+        GraphKind::Frequency,
+        GraphKind::Gpu,
+        GraphKind::Vram,
+        GraphKind::LoadAverage,
+        GraphKind::Memory,
+        GraphKind::Swap,
+        GraphKind::Network,
+        GraphKind::DiskIo,
+        GraphKind::Temperatures,
+        GraphKind::ProcessCount,
+        GraphKind::ProcessCpu,
+        GraphKind::ProcessMemory,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            GraphKind::Cpu => "CPU usage",
+            GraphKind::CpuTime => "CPU time breakdown",
+            GraphKind::Frequency => "CPU frequency",
+            GraphKind::Gpu => "GPU usage",
+            GraphKind::Vram => "VRAM usage",
+            GraphKind::LoadAverage => "Load average",
+            GraphKind::Memory => "Memory usage",
+            GraphKind::Swap => "Swap usage",
+            GraphKind::Network => "Network throughput",
+            GraphKind::DiskIo => "Disk I/O",
+            GraphKind::Temperatures => "Temperatures",
+            GraphKind::ProcessCount => "Process count",
+            GraphKind::ProcessCpu => "Process CPU usage",
+            GraphKind::ProcessMemory => "Process memory usage",
+        }
+    }
+}
+
+/// A plot of the graphs view: any number of series sharing the same y axis.
+struct Graph {
+    title: &'static str,
+    max_y: f64,
+    series: Vec<(String, Vec<[f64;2]>)>,
+    /// Horizontal markers, such as critical temperatures.
+    thresholds: Vec<(String, f64)>,
+    /// Whether the series are drawn as stacked areas.
+    stacked: bool,
+    /// Number of trailing series that are details, drawn as thin lines.
+    overlays: usize,
+    /// Vertical markers, such as processes being killed.
+    markers: Vec<(f64, String)>,
+}
+
+impl Graph {
+    fn new(title: &'static str, max_y: f64, series: Vec<(String, Vec<[f64;2]>)>) -> Self {
+        Self {
+            title,
+            max_y,
+            series,
+            thresholds: vec![],
+            stacked: false,
+            overlays: 0,
+            markers: vec![],
         }
     }
 }
 
 impl GraphsState {
+    /// Scrolls to the given graph, adding it if it is not shown.
+    pub fn focus(&mut self, kind: GraphKind) {
+        self.focus = Some(kind);
+        self.scroll_to_focus = true;
+    }
+
     pub fn graphs_view(
         &mut self,
         ctx: &egui::Context,
         _frame: &mut eframe::Frame,
-        system: Arc<Mutex<System>>,
+        history: Arc<Mutex<History>>,
+        settings: Arc<Mutex<Settings>>,
     ) {
-        if self.thread.is_none() {
-            let system = system.clone();
-            let points = self.points.clone();
-            let secs = self.secs.clone();
-            let ctx = ctx.clone();
-            let help = move || {
-                loop {
-                    std::thread::sleep(std::time::Duration::from_secs(1));
-                    let system = system.lock().unwrap();
-                    let mut points = points.lock().unwrap();
-                    let mut secs = secs.lock().unwrap();
-                    let plot_point = [
-                        secs.to_owned() as f64,
-                        system.global_cpu_info().cpu_usage() as f64
-                    ];
-                    points.push(plot_point);
-                    ctx.request_repaint();
-                    *secs += 1;
-                }
-            };
-            self.thread = Some(std::thread::spawn(help));
-        }
         egui::CentralPanel::default().show(ctx, |ui| {
-            let points = self.points.lock().unwrap();
-            let line = Line::new(points.iter().cloned().collect::<Vec<[f64;2]>>());
-            let secs = self.secs.lock().unwrap().to_owned();
-            let plot_bounds = if secs > 60 {
-                PlotBounds::from_min_max([-60.0 + (secs as f64), 0.0], [0.0 + (secs as f64), 100.0])
+            let mut history = history.lock().unwrap();
+            let mut settings = settings.lock().unwrap();
+            ui.horizontal(|ui| {
+                let pause_label = if self.paused_at.is_some() { "Resume" } else { "Pause" };
+                if ui.button(pause_label).clicked() {
+                    self.paused_at = match self.paused_at {
+                        Some(_) => None,
+                        None => Some(history.secs),
+                    };
+                }
+                if ui
+                    .add_enabled(!self.follow_live, egui::Button::new("Follow live"))
+                    .on_hover_text("Reset the plots to the latest samples. Double-clicking a plot does the same.")
+                    .clicked()
+                {
+                    self.follow_live = true;
+                }
+                let retention = settings.graph_retention.secs();
+                let window_label = WINDOWS
+                    .iter()
+                    .find(|(secs, _)| *secs == self.window_secs.min(retention))
+                    .map_or("", |(_, label)| label);
+                egui::ComboBox::from_id_source("graph_window")
+                    .selected_text(window_label)
+                    .show_ui(ui, |ui| {
+                        for (secs, label) in WINDOWS.into_iter().filter(|(secs, _)| *secs <= retention) {
+                            if ui.selectable_label(self.window_secs.min(retention) == secs, label).clicked() {
+                                self.window_secs = secs;
+                                self.follow_live = true;
+                            }
+                        }
+                    })
+                    .response
+                    .on_hover_text("Time shown while following the live data, up to the retention chosen in the settings.");
+                egui::ComboBox::from_id_source("cpu_mode")
+                    .selected_text(self.cpu_mode.label())
+                    .show_ui(ui, |ui| {
+                        for mode in CpuMode::ALL {
+                            ui.selectable_value(&mut self.cpu_mode, mode, mode.label());
+                        }
+                    });
+                ui.label("Smoothing");
+                ui.add(egui::DragValue::new(&mut self.smoothing)
+                    .clamp_range(0..=120)
+                    .suffix(" samples")
+                ).on_hover_text("Overlay a moving average of the CPU usage. 0 disables it.");
+                ui.label("Top processes");
+                ui.add(egui::DragValue::new(&mut history.top_count).clamp_range(0..=10))
+                    .on_hover_text("Overlay the most CPU hungry processes on the CPU graph. 0 disables it.");
+                ui.menu_button("Layout", |ui| settings.graphs_layout.layout_ui(ui));
+                ui.menu_button("Interfaces", |ui| {
+                    let network_graph = &mut settings.network_graph;
+                    ui.checkbox(&mut network_graph.per_interface, "One series per interface");
+                    ui.separator();
+                    for interface in &history.interfaces {
+                        let mut included = !network_graph.excluded.contains(&interface.name);
+                        if ui.checkbox(&mut included, &interface.name).changed() {
+                            if included {
+                                network_graph.excluded.retain(|name| *name != interface.name);
+                            } else {
+                                network_graph.excluded.push(interface.name.clone());
+                            }
+                        }
+                    }
+                });
+                ui.menu_button("Disks", |ui| {
+                    let excluded = &mut settings.disk_graph.excluded;
+                    for disk in &history.disks {
+                        let mut included = !excluded.contains(&disk.name);
+                        if ui.checkbox(&mut included, &disk.name).changed() {
+                            if included {
+                                excluded.retain(|name| *name != disk.name);
+                            } else {
+                                excluded.push(disk.name.clone());
+                            }
+                        }
+                    }
+                });
+                ui.menu_button("Processes", |ui| {
+                    if history.watched.is_empty() {
+                        ui.label("Use \"Plot this process\" in the process list to add one.");
+                    }
+                    let mut unwatch = None;
+                    for watched in &history.watched {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{} ({})", watched.name, watched.pid));
+                            if ui.small_button("✖").clicked() {
+                                unwatch = Some(watched.pid);
+                            }
+                        });
+                    }
+                    if let Some(pid) = unwatch {
+                        history.unwatch(pid);
+                    }
+                });
+                if let Some(status) = &self.export_status {
+                    ui.label(status);
+                }
+            });
+            let max_x = self.paused_at.unwrap_or(history.secs);
+            let x_range = (max_x - self.window_secs.min(settings.graph_retention.secs()), max_x);
+            let mut kinds = settings.graphs_layout.graphs.clone();
+            if let Some(focus) = self.focus.filter(|focus| !kinds.contains(focus)) {
+                kinds.push(focus);
+            }
+            let mut graphs = kinds
+                .into_iter()
+                .filter_map(|kind| Some((kind, self.graph(kind, &history, &settings, x_range.0)?)))
+                .collect::<Vec<_>>()
+                .into_iter();
+            let columns = settings.graphs_layout.columns.max(1);
+            drop(history);
+            drop(settings);
+
+            egui::ScrollArea::vertical().show(ui, |ui| loop {
+                let row = graphs.by_ref().take(columns).collect::<Vec<_>>();
+                if row.is_empty() {
+                    break;
+                }
+                ui.columns(columns, |uis| {
+                    for (ui, (kind, graph)) in uis.iter_mut().zip(row) {
+                        self.plot(ui, x_range, graph);
+                        if self.scroll_to_focus && self.focus == Some(kind) {
+                            ui.scroll_to_rect(ui.min_rect(), Some(egui::Align::TOP));
+                            self.scroll_to_focus = false;
+                        }
+                    }
+                });
+            });
+        });
+    }
+
+    /// Builds the given graph, if there is anything to show in it.
+    fn graph(&self, kind: GraphKind, history: &History, settings: &Settings, min_x: f64) -> Option<Graph> {
+        let title = kind.title();
+        let normalization = settings.cpu_normalization;
+        let cores = history.cores.len();
+        // Memory is shown in GiB, processes memory in MiB and throughputs in
+        // KiB/s, or their equivalent in the chosen units.
+        let (gib, gib_name) = settings.byte_units.unit(3);
+        let (mib, mib_name) = settings.byte_units.unit(2);
+        let (kib, kib_name) = settings.byte_units.unit(1);
+        let graph = match kind {
+            GraphKind::Cpu => {
+                let machine = |points: Vec<[f64;2]>| {
+                    points
+                        .into_iter()
+                        .map(|[x, y]| [x, normalization.machine_usage(y, cores)])
+                        .collect::<Vec<_>>()
+                };
+                let mut cpu = match self.cpu_mode {
+                    CpuMode::Total => vec![("CPU".to_string(), machine(history.cpu.points()))],
+                    CpuMode::PerCore => named_points(&history.cores),
+                    // Each core accounts for its share of the total usage.
+                    CpuMode::Stacked => named_points(&history.cores)
+                        .into_iter()
+                        .map(|(name, points)| {
+                            (name, points.into_iter().map(|[x, y]| [x, normalization.core_usage(y, cores)]).collect())
+                        })
+                        .collect(),
+                };
+                if self.smoothing > 1 {
+                    cpu.push((
+                        format!("CPU ({} samples average)", self.smoothing),
+                        machine(moving_average(&history.cpu.points(), self.smoothing)),
+                    ));
+                }
+                // The processes that used the most CPU in the window, unless
+                // they would be stacked with the cores.
+                let mut top = vec![];
+                if self.cpu_mode != CpuMode::Stacked {
+                    top = history
+                        .top_processes
+                        .iter()
+                        .map(|process| (process, window_max(min_x, [&process.cpu])))
+                        .collect::<Vec<_>>();
+                    top.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+                    top.truncate(history.top_count);
+                }
+                let overlays = top.len();
+                cpu.extend(top.into_iter().map(|(process, _)| {
+                    let points = process
+                        .cpu
+                        .iter()
+                        .map(|[x, y]| [x, normalization.core_usage(y, cores)])
+                        .collect();
+                    (format!("{} ({})", process.name, process.pid), points)
+                }));
+                // Single cores always go up to 100%, whatever the normalization.
+                let max_cpu = if self.cpu_mode == CpuMode::PerCore {
+                    100.0
+                } else {
+                    normalization.machine_usage(100.0, cores)
+                };
+                Graph {
+                    stacked: self.cpu_mode == CpuMode::Stacked,
+                    overlays,
+                    ..Graph::new(title, max_cpu, cpu)
+                }
+            }
+            GraphKind::CpuTime => {
+                let names = ["User", "System", "I/O wait", "Steal"];
+                let cpu_time = names
+                    .iter()
+                    .zip(&history.cpu_time)
+                    .map(|(name, series)| {
+                        let points = series
+                            .iter()
+                            .map(|[x, y]| [x, normalization.machine_usage(y, cores)])
+                            .collect();
+                        (name.to_string(), points)
+                    })
+                    .collect();
+                Graph {
+                    stacked: true,
+                    ..Graph::new(title, normalization.machine_usage(100.0, cores), cpu_time)
+                }
+            }
+            GraphKind::Frequency => {
+                let frequency = if self.cpu_mode != CpuMode::Total {
+                    named_points(&history.cores_frequency)
+                } else {
+                    vec![("Average (MHz)".to_string(), history.frequency.points())]
+                };
+                let max_frequency = window_max(min_x, history.cores_frequency.iter().map(|(_, points)| points));
+                Graph::new(title, max_frequency, frequency)
+            }
+            GraphKind::Gpu if history.gpus.is_empty() => return None,
+            GraphKind::Gpu => Graph::new(title, 100.0, named_points(&history.gpus)),
+            GraphKind::Vram if history.gpus.is_empty() => return None,
+            GraphKind::Vram => Graph::new(
+                title,
+                (history.total_gpu_memory / gib).max(1.0),
+                named_points(&history.gpus_memory)
+                    .into_iter()
+                    .map(|(name, points)| (format!("{} ({})", name, gib_name), divided(points, gib)))
+                    .collect(),
+            ),
+            GraphKind::LoadAverage => Graph::new(title, window_max(min_x, &history.load_average), vec![
+                ("1 minute".to_string(), history.load_average[0].points()),
+                ("5 minutes".to_string(), history.load_average[1].points()),
+                ("15 minutes".to_string(), history.load_average[2].points()),
+            ]),
+            // Stacked so that the page cache, which the kernel gives back
+            // when needed, is not mistaken for used memory.
+            GraphKind::Memory => Graph {
+                stacked: true,
+                ..Graph::new(title, (history.total_memory / gib).max(1.0), vec![
+                    (format!("Used ({})", gib_name), divided(history.memory.points(), gib)),
+                    (format!("Cache and buffers ({})", gib_name), divided(history.cache.points(), gib)),
+                    (format!("Free ({})", gib_name), divided(history.free.points(), gib)),
+                ])
+            },
+            GraphKind::Swap => Graph::new(title, (history.total_swap / gib).max(1.0), vec![
+                (format!("Swap ({})", gib_name), divided(history.swap.points(), gib)),
+            ]),
+            GraphKind::Network => {
+                let interfaces = history
+                    .interfaces
+                    .iter()
+                    .filter(|interface| !settings.network_graph.excluded.contains(&interface.name))
+                    .collect::<Vec<_>>();
+                let network = if settings.network_graph.per_interface {
+                    interfaces
+                        .iter()
+                        .flat_map(|interface| {
+                            [
+                                (
+                                    format!("{} received ({}/s)", interface.name, kib_name),
+                                    divided(interface.rates[0].points(), kib),
+                                ),
+                                (
+                                    format!("{} transmitted ({}/s)", interface.name, kib_name),
+                                    divided(interface.rates[1].points(), kib),
+                                ),
+                            ]
+                        })
+                        .collect()
+                } else {
+                    vec![
+                        (
+                            format!("Received ({}/s)", kib_name),
+                            divided(series::sum_points(interfaces.iter().map(|interface| &interface.rates[0])), kib),
+                        ),
+                        (
+                            format!("Transmitted ({}/s)", kib_name),
+                            divided(series::sum_points(interfaces.iter().map(|interface| &interface.rates[1])), kib),
+                        ),
+                    ]
+                };
+                let max_network = network
+                    .iter()
+                    .flat_map(|(_, points)| points)
+                    .filter(|[x, _]| *x >= min_x)
+                    .fold(1.0, |max, [_, y]| y.max(max));
+                Graph::new(title, max_network, network)
+            }
+            GraphKind::ProcessCount => Graph::new(title, window_max(min_x, [&history.processes]), vec![
+                ("Processes".to_string(), history.processes.points()),
+            ]),
+            GraphKind::ProcessCpu | GraphKind::ProcessMemory if history.watched.is_empty() => return None,
+            GraphKind::ProcessCpu => {
+                let cpu = history
+                    .watched
+                    .iter()
+                    .map(|watched| {
+                        let points = watched
+                            .cpu
+                            .iter()
+                            .map(|[x, y]| [x, normalization.core_usage(y, cores)])
+                            .collect();
+                        (format!("{} ({})", watched.name, watched.pid), points)
+                    })
+                    .collect();
+                // With the per core normalization, a process using several
+                // cores goes over 100%.
+                let max_cpu = window_max(min_x, history.watched.iter().map(|watched| &watched.cpu));
+                Graph::new(title, normalization.core_usage(max_cpu, cores).max(100.0), cpu)
+            }
+            GraphKind::ProcessMemory => {
+                let memory = history
+                    .watched
+                    .iter()
+                    .map(|watched| {
+                        let name = format!("{} ({}) ({})", watched.name, watched.pid, mib_name);
+                        (name, divided(watched.memory.points(), mib))
+                    })
+                    .collect();
+                let max_memory = window_max(min_x, history.watched.iter().map(|watched| &watched.memory)) / mib;
+                Graph::new(title, max_memory.max(1.0), memory)
+            }
+            GraphKind::DiskIo => {
+                let disks = history
+                    .disks
+                    .iter()
+                    .filter(|disk| !settings.disk_graph.excluded.contains(&disk.name))
+                    .collect::<Vec<_>>();
+                if disks.is_empty() {
+                    return None;
+                }
+                let io = disks
+                    .iter()
+                    .flat_map(|disk| {
+                        [
+                            (format!("{} read ({}/s)", disk.name, kib_name), divided(disk.rates[0].points(), kib)),
+                            (format!("{} written ({}/s)", disk.name, kib_name), divided(disk.rates[1].points(), kib)),
+                        ]
+                    })
+                    .collect();
+                let max_io = window_max(min_x, disks.iter().flat_map(|disk| &disk.rates)) / kib;
+                Graph::new(title, max_io.max(1.0), io)
+            }
+            GraphKind::Temperatures => {
+                let unit = settings.temperature_unit;
+                let max_temperature = window_max(min_x, history.temperatures.iter().map(|(_, points)| points))
+                    .max(history.temperatures_critical.iter().fold(0.0, |max, (_, c)| c.max(max)));
+                let temperatures = named_points(&history.temperatures)
+                    .into_iter()
+                    .map(|(name, points)| {
+                        let points = points.into_iter().map(|[x, y]| [x, unit.convert(y)]).collect();
+                        (format!("{} ({})", name, unit.symbol()), points)
+                    })
+                    .collect();
+                Graph {
+                    thresholds: history
+                        .temperatures_critical
+                        .iter()
+                        .map(|(name, critical)| (name.clone(), unit.convert(*critical)))
+                        .collect(),
+                    ..Graph::new(title, unit.convert(max_temperature), temperatures)
+                }
+            }
+        };
+        Some(Graph {
+            markers: history.events.clone(),
+            ..graph
+        })
+    }
+
+    /// Shows a graph with its legend. The plot follows the latest samples,
+    /// unless the user has interacted with one of the plots.
+    fn plot(&mut self, ui: &mut egui::Ui, (min_x, max_x): (f64, f64), graph: Graph) {
+        let log_scale = self.log_scale.contains(graph.title);
+        let mut export = None;
+        ui.horizontal_wrapped(|ui| {
+            ui.strong(graph.title);
+            if ui.selectable_label(log_scale, "log").on_hover_text("Logarithmic scale").clicked() {
+                if log_scale {
+                    self.log_scale.remove(graph.title);
+                } else {
+                    self.log_scale.insert(graph.title);
+                }
+            }
+            ui.menu_button("Export", |ui| {
+                ui.label("File");
+                ui.text_edit_singleline(&mut self.export_path);
+                ui.horizontal(|ui| {
+                    if ui.button("Save as PNG").clicked() {
+                        export = Some(Path::new(&self.export_path).with_extension("png"));
+                        ui.close_menu();
+                    }
+                    if ui.button("Save as SVG").clicked() {
+                        export = Some(Path::new(&self.export_path).with_extension("svg"));
+                        ui.close_menu();
+                    }
+                });
+            });
+            for (index, (name, _)) in graph.series.iter().enumerate() {
+                let key = (graph.title.to_string(), name.clone());
+                let visible = !self.hidden.contains(&key);
+                let text = RichText::new(name).color(if visible {
+                    series_color(index)
+                } else {
+                    Color32::GRAY
+                });
+                if ui.selectable_label(visible, text).clicked() {
+                    if visible {
+                        self.hidden.insert(key);
+                    } else {
+                        self.hidden.remove(&key);
+                    }
+                }
+            }
+        });
+
+        let first_overlay = graph.series.len() - graph.overlays;
+        let series = graph
+            .series
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (name, _))| !self.hidden.contains(&(graph.title.to_string(), name.clone())))
+            .collect::<Vec<_>>();
+        let stacked = graph.stacked;
+        let scale = |y: f64| if log_scale { log_scale_value(y) } else { y };
+        let unscale = move |y: f64| if log_scale { 10f64.powf(y) - 1.0 } else { y };
+        let max_y = scale(graph.max_y);
+        let thresholds = graph
+            .thresholds
+            .into_iter()
+            .map(|(name, y)| (name, scale(y)))
+            .collect::<Vec<_>>();
+
+        // Lines as drawn: stacked on top of each other if needed, then scaled.
+        let mut lines = vec![];
+        let mut lower: Vec<[f64;2]> = vec![];
+        for (index, (name, points)) in &series {
+            let points = if stacked {
+                let upper = points
+                    .iter()
+                    .enumerate()
+                    .map(|(i, [x, y])| [*x, y + lower.get(i).map_or(0.0, |[_, y]| *y)])
+                    .collect::<Vec<_>>();
+                lower = upper.clone();
+                upper
             } else {
-                PlotBounds::from_min_max([0.0, 0.0], [60.0, 100.0])
+                points.clone()
             };
-            Plot::new("CPU usage").view_aspect(2.0).show(ui, |plot_ui| {
-                if !self.plot_clicked {
-                    plot_ui.set_plot_bounds(plot_bounds);
+            let points = points.into_iter().map(|[x, y]| [x, scale(y)]).collect::<Vec<_>>();
+            lines.push((name.clone(), series_color(*index), points));
+        }
+
+        let mut plot = Plot::new(graph.title)
+            .height(PLOT_HEIGHT)
+            .link_axis(self.time_axis.clone())
+            .x_axis_formatter(|x, _| format_clock(x as i64))
+            // Let the mouse wheel scroll through the graphs, zooming is done
+            // with Ctrl and the wheel.
+            .allow_scroll(false)
+            // The hovered samples are shown in a tooltip instead.
+            .show_x(false)
+            .show_y(false);
+        if log_scale {
+            plot = plot.y_axis_formatter(move |y, _| format_value(unscale(y)));
+        }
+        let response = plot.show(ui, |plot_ui| {
+            if self.follow_live {
+                plot_ui.set_plot_bounds(PlotBounds::from_min_max([min_x, 0.0], [max_x, max_y]));
+            }
+            let hovered = plot_ui
+                .pointer_coordinate()
+                .filter(|_| plot_ui.plot_hovered())
+                .and_then(|pointer| {
+                    let samples = series
+                        .iter()
+                        .filter_map(|(_, (name, points))| {
+                            nearest_sample(points, pointer.x).map(|point| (name.clone(), point))
+                        })
+                        .collect::<Vec<_>>();
+                    let x = samples
+                        .iter()
+                        .map(|(_, [x, _])| *x)
+                        .min_by(|a, b| (a - pointer.x).abs().total_cmp(&(b - pointer.x).abs()))?;
+                    Some((x, samples))
+                });
+            if stacked {
+                let zero = vec![];
+                let bottoms = std::iter::once(&zero).chain(lines.iter().map(|(_, _, points)| points));
+                for ((_, color, upper), lower) in lines.iter().zip(bottoms) {
+                    let below = |i: usize| lower.get(i).map_or(0.0, |[_, y]: &[f64;2]| *y);
+                    for (i, segment) in upper.windows(2).enumerate().filter(|(_, s)| s[1][0] >= min_x) {
+                        plot_ui.polygon(
+                            Polygon::new(vec![
+                                [segment[0][0], below(i)],
+                                segment[0],
+                                segment[1],
+                                [segment[1][0], below(i + 1)],
+                            ])
+                            .color(*color)
+                            .stroke(Stroke::NONE)
+                            .fill_alpha(0.5),
+                        );
+                    }
                 }
-                plot_ui.line(line);
-                if plot_ui.plot_clicked() {
-                    self.plot_clicked = true;
+            }
+            for ((index, _), (name, color, points)) in series.iter().zip(&lines) {
+                let mut line = Line::new(points.clone()).name(name).color(*color);
+                if *index >= first_overlay {
+                    line = line.width(0.75);
                 }
-            })
+                plot_ui.line(line);
+            }
+            for (name, y) in &thresholds {
+                plot_ui.hline(
+                    HLine::new(*y)
+                        .name(name)
+                        .color(Color32::RED)
+                        .style(LineStyle::dashed_loose()),
+                );
+            }
+            for (x, label) in &graph.markers {
+                plot_ui.vline(VLine::new(*x).color(Color32::GOLD).style(LineStyle::dashed_dense()));
+                plot_ui.text(
+                    Text::new(PlotPoint::new(*x, max_y), label.as_str())
+                        .color(Color32::GOLD)
+                        .anchor(Align2::LEFT_TOP),
+                );
+            }
+            if let Some((x, _)) = hovered {
+                plot_ui.vline(VLine::new(x).color(Color32::GRAY));
+            }
+            let zoomed = plot_ui.plot_hovered() && plot_ui.ctx().input(|i| i.zoom_delta() != 1.0);
+            if plot_ui.pointer_coordinate_drag_delta() != egui::Vec2::ZERO || zoomed {
+                self.follow_live = false;
+            }
+            (hovered, plot_ui.plot_bounds())
         });
+        let (hovered, bounds) = response.inner;
+        if response.response.double_clicked() {
+            self.follow_live = true;
+        }
+        if let Some((x, samples)) = hovered {
+            egui::show_tooltip_at_pointer(ui.ctx(), response.response.id.with("tooltip"), |ui| {
+                ui.strong(format_clock(x as i64));
+                for (name, [_, y]) in samples {
+                    ui.label(format!("{}: {:.2}", name, y));
+                }
+            });
+        }
+
+        if let Some(path) = export {
+            let chart = Chart {
+                title: graph.title,
+                bounds: (bounds.min(), bounds.max()),
+                lines: &lines,
+                stacked,
+                thresholds: &thresholds,
+                markers: &graph.markers,
+                x_label: &|x| format_clock(x as i64),
+                y_label: &|y| format_value(unscale(y)),
+            };
+            self.export_status = Some(match chart.save(&path) {
+                Ok(()) => format!("Saved {}", path.display()),
+                Err(error) => format!("Could not save {}: {}", path.display(), error),
+            });
+        }
+    }
+}
+
+/// Formats an axis value, with a decimal for small values.
+fn format_value(y: f64) -> String {
+    format!("{:.*}", if y.abs() < 10.0 { 1 } else { 0 }, y)
+}
+
+/// Maps a value to a log10 scale, offset by one so that 0 stays at 0.
+fn log_scale_value(y: f64) -> f64 {
+    (y.max(0.0) + 1.0).log10()
+}
+
+/// Color of the n-th series of a graph, spread around the hue circle.
+fn series_color(index: usize) -> Color32 {
+    let golden_ratio = (5.0_f32.sqrt() - 1.0) / 2.0;
+    Hsva::new(index as f32 * golden_ratio, 0.85, 0.5, 1.0).into()
+}
+
+fn named_points(series: &[(String, Series)]) -> Vec<(String, Vec<[f64;2]>)> {
+    series
+        .iter()
+        .map(|(name, series)| (name.clone(), series.points()))
+        .collect()
+}
+
+/// Average of each point with the `window - 1` points before it.
+fn moving_average(points: &[[f64;2]], window: usize) -> Vec<[f64;2]> {
+    let mut sum = 0.0;
+    points
+        .iter()
+        .enumerate()
+        .map(|(index, [x, y])| {
+            sum += y;
+            if index >= window {
+                sum -= points[index - window][1];
+            }
+            [*x, sum / (index + 1).min(window) as f64]
+        })
+        .collect()
+}
+
+/// Sample of `points` closest to `x`. The points must be sorted by x.
+fn nearest_sample(points: &[[f64;2]], x: f64) -> Option<[f64;2]> {
+    let index = points.partition_point(|[px, _]| *px < x);
+    let before = index.checked_sub(1).and_then(|i| points.get(i));
+    let after = points.get(index);
+    match (before, after) {
+        (Some(before), Some(after)) => {
+            if x - before[0] <= after[0] - x {
+                Some(*before)
+            } else {
+                Some(*after)
+            }
+        }
+        (Some(point), None) | (None, Some(point)) => Some(*point),
+        (None, None) => None,
     }
 }
+
+/// The points with their values divided, to show them in another unit.
+fn divided(points: Vec<[f64;2]>, divisor: f64) -> Vec<[f64;2]> {
+    points.into_iter().map(|[x, y]| [x, y / divisor]).collect()
+}
+
+/// Highest value of the given series since `min_x`, used to scale plots
+/// without a natural upper bound. Never smaller than 1.
+fn window_max<'a>(min_x: f64, series: impl IntoIterator<Item = &'a Series>) -> f64 {
+    series
+        .into_iter()
+        .flat_map(Series::iter)
+        .filter(|[x, _]| *x >= min_x)
+        .fold(1.0, |max, [_, y]| y.max(max))
+}
@@ -0,0 +1,90 @@
+use egui::RichText;
+
+use crate::keybindings::Keybindings;
+
+/// The pages of the help window.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum HelpPage {
+    Shortcuts,
+    Search,
+    About,
+}
+
+impl HelpPage {
+    pub const ALL: [HelpPage; 3] = [HelpPage::Shortcuts, HelpPage::Search, HelpPage::About];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HelpPage::Shortcuts => "Keyboard shortcuts",
+            HelpPage::Search => "Search syntax",
+            HelpPage::About => "About",
+        }
+    }
+}
+
+/// Shows the help window while a page is open, closing it sets the page to
+/// `None`.
+pub fn help_window(ctx: &egui::Context, page: &mut Option<HelpPage>, keybindings: &Keybindings) {
+    let Some(current) = page else {
+        return;
+    };
+    let mut open = true;
+    egui::Window::new("Help").open(&mut open).resizable(false).show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            for other in HelpPage::ALL {
+                ui.selectable_value(current, other, other.label());
+            }
+        });
+        ui.separator();
+        match current {
+            HelpPage::Shortcuts => {
+                keybindings.shortcuts_ui(ui);
+                ui.label("Shortcuts can be changed in File > Settings.");
+                ui.add_space(8.0);
+                ui.label(RichText::new("Process list").strong());
+                ui.label("Up and Down move the selection, Shift+Up and Shift+Down extend it, and Enter shows the details of the selected process.");
+            }
+            HelpPage::Search => search_help(ui),
+            HelpPage::About => about(ui),
+        }
+    });
+    if !open {
+        *page = None;
+    }
+}
+
+fn search_help(ui: &mut egui::Ui) {
+    ui.label(RichText::new("Plain search").strong());
+    ui.label("Shows the processes whose name contains the search, ignoring the case unless \"Case sensitive\" is checked.");
+    ui.add_space(8.0);
+    ui.label(RichText::new("Regex").strong());
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Matches the names against a regular expression. Start it with");
+        ui.code("(?i)");
+        ui.label("to ignore the case.");
+    });
+    ui.add_space(8.0);
+    ui.label(RichText::new("Label search").strong());
+    ui.label("Searches by column, with labels separated by a single space. A process must match all of them.");
+    egui::Grid::new("labels").show(ui, |ui| {
+        for (label, description) in [
+            ("pid:643", "The process with this PID"),
+            ("owner:root", "The processes of this user"),
+            ("name:firefox", "The processes whose name contains this text"),
+            ("name:\"Web Content\"", "Quotes allow spaces and escapes such as \\n or \\u{00AC}"),
+        ] {
+            ui.code(label);
+            ui.label(description);
+            ui.end_row();
+        }
+    });
+    ui.label("When Regex is checked too, the name is a regular expression.");
+}
+
+fn about(ui: &mut egui::Ui) {
+    ui.heading("Task Manager");
+    ui.label(format!("Version {}", env!("CARGO_PKG_VERSION")));
+    ui.label(format!("By {}", env!("CARGO_PKG_AUTHORS")));
+    ui.add_space(8.0);
+    ui.label("A simple task manager, to see and kill processes and follow the usage of the system.");
+}
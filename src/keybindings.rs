@@ -0,0 +1,153 @@
+use egui::{Event, Key, KeyboardShortcut, Modifiers};
+use serde::{Deserialize, Serialize};
+
+/// Actions that can be triggered from the keyboard.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    KillSelected,
+    FocusSearch,
+    ProcessesView,
+    GraphsView,
+    PerformanceView,
+    Refresh,
+    Quit,
+    CommandPalette,
+}
+
+impl Action {
+    const ALL: [Action; 8] = [
+        Action::KillSelected,
+        Action::FocusSearch,
+        Action::ProcessesView,
+        Action::GraphsView,
+        Action::PerformanceView,
+        Action::Refresh,
+        Action::Quit,
+        Action::CommandPalette,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::KillSelected => "Kill the selected process",
+            Action::FocusSearch => "Focus the search",
+            Action::ProcessesView => "Processes view",
+            Action::GraphsView => "Graphs view",
+            Action::PerformanceView => "Performance view",
+            Action::Refresh => "Refresh now",
+            Action::Quit => "Quit",
+            Action::CommandPalette => "Command palette",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shortcut {
+    pub modifiers: Modifiers,
+    pub key: Key,
+}
+
+impl Shortcut {
+    fn new(modifiers: Modifiers, key: Key) -> Self {
+        Self { modifiers, key }
+    }
+
+    fn keyboard_shortcut(&self) -> KeyboardShortcut {
+        KeyboardShortcut::new(self.modifiers, self.key)
+    }
+}
+
+/// The shortcut bound to each action.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keybindings {
+    pub bindings: Vec<(Action, Shortcut)>,
+    /// The action waiting for a key press to be bound to it.
+    #[serde(skip)]
+    rebinding: Option<Action>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            bindings: vec![
+                (Action::KillSelected, Shortcut::new(Modifiers::NONE, Key::Delete)),
+                (Action::FocusSearch, Shortcut::new(Modifiers::COMMAND, Key::F)),
+                (Action::ProcessesView, Shortcut::new(Modifiers::COMMAND, Key::Num1)),
+                (Action::GraphsView, Shortcut::new(Modifiers::COMMAND, Key::Num2)),
+                (Action::PerformanceView, Shortcut::new(Modifiers::COMMAND, Key::Num3)),
+                (Action::Refresh, Shortcut::new(Modifiers::NONE, Key::F5)),
+                (Action::Quit, Shortcut::new(Modifiers::COMMAND, Key::Q)),
+                (Action::CommandPalette, Shortcut::new(Modifiers { shift: true, ..Modifiers::COMMAND }, Key::P)),
+            ],
+            rebinding: None,
+        }
+    }
+}
+
+impl Keybindings {
+    /// Consumes the shortcuts pressed since last frame and returns their
+    /// actions. Shortcuts without Ctrl or Alt are left to the text fields
+    /// while one is focused.
+    pub fn triggered(&self, ctx: &egui::Context) -> Vec<Action> {
+        if self.rebinding.is_some() {
+            return vec![];
+        }
+        let typing = ctx.wants_keyboard_input();
+        ctx.input_mut(|input| {
+            self.bindings
+                .iter()
+                .filter(|(_, shortcut)| !typing || shortcut.modifiers.command || shortcut.modifiers.alt)
+                .filter(|(_, shortcut)| input.consume_shortcut(&shortcut.keyboard_shortcut()))
+                .map(|(action, _)| *action)
+                .collect()
+        })
+    }
+
+    /// Lists the actions and their shortcut, without editing them.
+    pub fn shortcuts_ui(&self, ui: &mut egui::Ui) {
+        egui::Grid::new("shortcuts").show(ui, |ui| {
+            for action in Action::ALL {
+                ui.label(action.label());
+                let shortcut = self.bindings.iter().find(|(bound, _)| *bound == action).map(|(_, shortcut)| *shortcut);
+                ui.label(shortcut.map_or("None".to_string(), |shortcut| ui.ctx().format_shortcut(&shortcut.keyboard_shortcut())));
+                ui.end_row();
+            }
+        });
+    }
+
+    pub fn keybindings_ui(&mut self, ui: &mut egui::Ui) {
+        if let Some(action) = self.rebinding {
+            let pressed = ui.input(|input| {
+                input.events.iter().find_map(|event| match event {
+                    Event::Key { key, pressed: true, modifiers, .. } => Some(Shortcut::new(*modifiers, *key)),
+                    _ => None,
+                })
+            });
+            if let Some(shortcut) = pressed {
+                self.bindings.retain(|(bound, _)| *bound != action);
+                if shortcut.key != Key::Escape {
+                    self.bindings.push((action, shortcut));
+                }
+                self.rebinding = None;
+            }
+        }
+        egui::Grid::new("keybindings").show(ui, |ui| {
+            for action in Action::ALL {
+                ui.label(action.label());
+                let shortcut = self.bindings.iter().find(|(bound, _)| *bound == action).map(|(_, shortcut)| *shortcut);
+                let text = if self.rebinding == Some(action) {
+                    "Press a key, or Escape to unbind".to_string()
+                } else {
+                    shortcut.map_or("None".to_string(), |shortcut| ui.ctx().format_shortcut(&shortcut.keyboard_shortcut()))
+                };
+                if ui.button(text).clicked() {
+                    self.rebinding = Some(action);
+                }
+                ui.end_row();
+            }
+        });
+        if ui.button("Reset to defaults").clicked() {
+            *self = Self::default();
+        }
+    }
+}
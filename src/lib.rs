@@ -1,8 +1,44 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+mod alerts;
 mod app;
+mod autostart;
+mod cgroups;
+mod cli;
+mod connections;
+#[cfg(unix)]
+mod containers;
+mod cpu_time;
+mod disks;
+mod export;
+mod format;
+mod gpu;
 mod graphs;
+mod help;
+mod keybindings;
+mod logs;
+mod metrics;
+#[cfg(feature = "notifications")]
+mod notifications;
+mod open_files;
+mod palette;
 mod parse_labels;
+mod performance;
+mod power;
 mod process_list;
+mod recorder;
+mod rules;
+mod sensors;
+mod series;
+#[cfg(feature = "systemd")]
+mod services;
+mod sessions;
 mod settings;
+mod snapshot;
+mod startup;
+mod system_info;
+#[cfg(feature = "tray")]
+mod tray;
 pub use app::TaskManager;
+pub use cli::{Args, USAGE};
+pub use snapshot::Snapshot;
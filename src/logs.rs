@@ -0,0 +1,316 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex, Weak},
+    thread,
+    time::Duration,
+};
+
+use egui::{Color32, RichText};
+use egui_extras::{Column, TableBuilder};
+use sysinfo::{Pid, PidExt};
+
+use crate::format::format_time;
+
+/// Number of entries read at first, and kept as new ones come.
+const MAX_ENTRIES: usize = 5000;
+const SYSLOG: &str = "/var/log/syslog";
+
+/// Syslog priorities, from the most to the least severe.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Emergency,
+    Alert,
+    Critical,
+    Error,
+    Warning,
+    Notice,
+    Info,
+    Debug,
+}
+
+impl Priority {
+    const ALL: [Priority; 8] = [
+        Priority::Emergency,
+        Priority::Alert,
+        Priority::Critical,
+        Priority::Error,
+        Priority::Warning,
+        Priority::Notice,
+        Priority::Info,
+        Priority::Debug,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Priority::Emergency => "Emergency",
+            Priority::Alert => "Alert",
+            Priority::Critical => "Critical",
+            Priority::Error => "Error",
+            Priority::Warning => "Warning",
+            Priority::Notice => "Notice",
+            Priority::Info => "Info",
+            Priority::Debug => "Debug",
+        }
+    }
+
+    fn color(&self) -> Option<Color32> {
+        match self {
+            Priority::Emergency | Priority::Alert | Priority::Critical | Priority::Error => Some(Color32::RED),
+            Priority::Warning => Some(Color32::from_rgb(255, 165, 0)),
+            _ => None,
+        }
+    }
+}
+
+pub struct LogEntry {
+    /// Local time, as given by the source.
+    pub time: String,
+    pub priority: Priority,
+    /// Program that logged the entry.
+    pub identifier: String,
+    pub pid: Option<u32>,
+    pub message: String,
+}
+
+/// Parses an entry of `journalctl -o json`.
+fn journal_entry(line: &str) -> Option<LogEntry> {
+    let entry: serde_json::Value = serde_json::from_str(line).ok()?;
+    let field = |name: &str| entry.get(name).and_then(|value| value.as_str());
+    // Messages that are not valid UTF-8 are given as arrays of bytes.
+    let message = match entry.get("MESSAGE")? {
+        serde_json::Value::Array(bytes) => {
+            String::from_utf8_lossy(&bytes.iter().filter_map(|byte| byte.as_u64()).map(|byte| byte as u8).collect::<Vec<_>>())
+                .to_string()
+        }
+        message => message.as_str()?.to_string(),
+    };
+    Some(LogEntry {
+        time: format_time(field("__REALTIME_TIMESTAMP")?.parse::<i64>().ok()? / 1_000_000),
+        priority: field("PRIORITY")
+            .and_then(|priority| priority.parse::<usize>().ok())
+            .and_then(|priority| Priority::ALL.get(priority).copied())
+            .unwrap_or(Priority::Info),
+        identifier: field("SYSLOG_IDENTIFIER").or(field("_COMM")).unwrap_or_default().to_string(),
+        pid: field("_PID").and_then(|pid| pid.parse().ok()),
+        message,
+    })
+}
+
+/// Parses a line of the syslog file, such as
+/// `Jan  1 12:00:00 host sshd[123]: message`. The file has no priorities.
+fn syslog_entry(line: &str) -> LogEntry {
+    let (head, message) = line.split_once(": ").unwrap_or(("", line));
+    let mut tokens = head.split_whitespace().collect::<Vec<_>>();
+    let program = tokens.pop().unwrap_or_default();
+    let _host = tokens.pop();
+    let (identifier, pid) = match program.split_once('[') {
+        Some((identifier, pid)) => (identifier, pid.trim_end_matches(']').parse().ok()),
+        None => (program, None),
+    };
+    LogEntry {
+        time: tokens.join(" "),
+        priority: Priority::Info,
+        identifier: identifier.to_string(),
+        pid,
+        message: message.to_string(),
+    }
+}
+
+fn push(entries: &Mutex<VecDeque<LogEntry>>, entry: LogEntry) {
+    let mut entries = entries.lock().unwrap();
+    if entries.len() == MAX_ENTRIES {
+        entries.pop_front();
+    }
+    entries.push_back(entry);
+}
+
+pub struct LogsState {
+    entries: Arc<Mutex<VecDeque<LogEntry>>>,
+    /// The `journalctl` following the journal, stopped with the view.
+    journalctl: Option<Child>,
+    /// Where the entries come from, or why none can be read.
+    source: String,
+    search: String,
+    /// Least severe priority shown.
+    priority: Priority,
+}
+
+impl LogsState {
+    /// Starts reading the logs, until the view is closed.
+    pub fn new() -> Self {
+        let mut state = Self {
+            entries: Default::default(),
+            journalctl: None,
+            source: String::new(),
+            search: String::new(),
+            priority: Priority::Info,
+        };
+        state.start();
+        state
+    }
+
+    /// Starts following the journal, or the syslog file if there is no
+    /// journal.
+    fn start(&mut self) {
+        let journalctl = Command::new("journalctl")
+            .args(["--output=json", "--follow", "--no-pager", "--lines", &MAX_ENTRIES.to_string()])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn();
+        match journalctl {
+            Ok(mut journalctl) => {
+                let stdout = journalctl.stdout.take().unwrap();
+                let entries = self.entries.clone();
+                thread::spawn(move || {
+                    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                        if let Some(entry) = journal_entry(&line) {
+                            push(&entries, entry);
+                        }
+                    }
+                });
+                self.journalctl = Some(journalctl);
+                self.source = "journald".to_string();
+            }
+            Err(_) => match File::open(SYSLOG) {
+                Ok(file) => {
+                    follow_syslog(file, Arc::downgrade(&self.entries));
+                    self.source = SYSLOG.to_string();
+                }
+                Err(e) => self.source = format!("Neither journalctl nor {SYSLOG} can be read: {e}"),
+            },
+        }
+    }
+
+    /// Shows the system logs, latest last. Returns the process to show in the
+    /// process list, if one was clicked.
+    pub fn logs_view(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) -> Option<Pid> {
+        // New entries come from another thread.
+        ctx.request_repaint_after(Duration::from_secs(1));
+        let mut jump = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Search:");
+                ui.text_edit_singleline(&mut self.search);
+                egui::ComboBox::from_id_source("priority")
+                    .selected_text(format!("{} and above", self.priority.label()))
+                    .show_ui(ui, |ui| {
+                        for priority in Priority::ALL {
+                            ui.selectable_value(&mut self.priority, priority, priority.label());
+                        }
+                    });
+                ui.label(format!("From {}", self.source));
+            });
+
+            let search = self.search.to_lowercase();
+            let entries = self.entries.lock().unwrap();
+            let entries = entries
+                .iter()
+                .filter(|entry| entry.priority <= self.priority)
+                .filter(|entry| {
+                    search.is_empty()
+                        || entry.message.to_lowercase().contains(&search)
+                        || entry.identifier.to_lowercase().contains(&search)
+                })
+                .collect::<Vec<_>>();
+            let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+            TableBuilder::new(ui)
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .column(Column::auto().at_least(128.0))
+                .column(Column::auto().at_least(64.0))
+                .column(Column::auto().at_least(128.0))
+                .column(Column::remainder())
+                .stick_to_bottom(true)
+                .min_scrolled_height(0.0)
+                .header(20.0, |mut header| {
+                    for title in ["time", "priority", "program", "message"] {
+                        header.col(|ui| {
+                            ui.strong(title);
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(text_height, entries.len(), |index, mut row| {
+                        let entry = entries[index];
+                        row.col(|ui| {
+                            ui.label(&entry.time);
+                        });
+                        row.col(|ui| {
+                            let text = RichText::new(entry.priority.label());
+                            ui.label(match entry.priority.color() {
+                                Some(color) => text.color(color),
+                                None => text,
+                            });
+                        });
+                        row.col(|ui| match entry.pid {
+                            Some(pid) => {
+                                if ui
+                                    .link(format!("{}[{pid}]", entry.identifier))
+                                    .on_hover_text("Show in the process list")
+                                    .clicked()
+                                {
+                                    jump = Some(Pid::from_u32(pid));
+                                }
+                            }
+                            None => {
+                                ui.label(&entry.identifier);
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(&entry.message);
+                        });
+                    });
+                });
+        });
+        jump
+    }
+}
+
+impl Drop for LogsState {
+    fn drop(&mut self) {
+        if let Some(journalctl) = &mut self.journalctl {
+            let _ = journalctl.kill();
+            let _ = journalctl.wait();
+        }
+    }
+}
+
+/// Reads the end of the syslog file, then the lines appended to it, until
+/// the view is closed.
+fn follow_syslog(mut file: File, entries: Weak<Mutex<VecDeque<LogEntry>>>) {
+    thread::spawn(move || {
+        let mut contents = String::new();
+        if file.read_to_string(&mut contents).is_err() {
+            return;
+        }
+        let Some(shared) = entries.upgrade() else {
+            return;
+        };
+        let lines = contents.lines().collect::<Vec<_>>();
+        for line in &lines[lines.len().saturating_sub(MAX_ENTRIES)..] {
+            push(&shared, syslog_entry(line));
+        }
+        drop(shared);
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        loop {
+            thread::sleep(Duration::from_secs(1));
+            let Some(shared) = entries.upgrade() else {
+                return;
+            };
+            loop {
+                line.clear();
+                match reader.read_line(&mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => push(&shared, syslog_entry(line.trim_end())),
+                }
+            }
+            // The file was rotated.
+            if reader.get_ref().metadata().map_or(false, |metadata| metadata.len() < reader.stream_position().unwrap_or(0)) {
+                let _ = reader.seek(SeekFrom::Start(0));
+            }
+        }
+    });
+}
@@ -7,10 +7,31 @@ fn main() -> eframe::Result<()> {
     // Log to stdout (if you run with `RUST_LOG=debug`).
     tracing_subscriber::fmt::init();
 
-    let native_options = eframe::NativeOptions::default();
+    let args = match task_manager::Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{error}\n\n{}", task_manager::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{}", task_manager::USAGE);
+        return Ok(());
+    }
+    if let Some(format) = args.dump {
+        print!("{}", task_manager::Snapshot::collect().format(format));
+        return Ok(());
+    }
+
+    let native_options = eframe::NativeOptions {
+        // Needed for the "Follow system" theme.
+        follow_system_theme: true,
+        maximized: args.maximized,
+        ..Default::default()
+    };
     eframe::run_native(
         "Task Manager",
         native_options,
-        Box::new(|cc| Box::new(task_manager::TaskManager::new(cc))),
+        Box::new(|cc| Box::new(task_manager::TaskManager::new(cc, args))),
     )
 }
@@ -0,0 +1,425 @@
+use std::{
+    collections::HashMap,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use sysinfo::{ComponentExt, CpuExt, DiskExt, NetworkExt, NetworksExt, Pid, ProcessExt, System, SystemExt};
+
+use crate::{
+    cpu_time::{self, CpuTimes},
+    disks,
+    gpu::Gpus,
+    series::Series,
+    settings::{Level, TemperatureUnit, Threshold, Thresholds},
+};
+
+/// History of the system metrics, recorded after every refresh by the
+/// thread started in `TaskManager::new` and rendered by the graphs view.
+///
+/// Sizes are in bytes, and converted to the unit picked in the settings
+/// when displayed.
+#[derive(Default)]
+pub struct History {
+    pub cpu: Series,
+    pub cores: Vec<(String, Series)>,
+    /// Share of the CPU time spent in user, system, I/O wait and steal
+    /// time, in percent.
+    pub cpu_time: [Series; 4],
+    pub frequency: Series,
+    pub cores_frequency: Vec<(String, Series)>,
+    pub load_average: [Series; 3],
+    pub memory: Series,
+    /// Memory that is in use but can be reclaimed, mostly the page cache.
+    pub cache: Series,
+    /// Memory that is not used at all.
+    pub free: Series,
+    pub swap: Series,
+    pub interfaces: Vec<Throughput>,
+    pub disks: Vec<Throughput>,
+    pub gpus: Vec<(String, Series)>,
+    pub gpus_memory: Vec<(String, Series)>,
+    pub total_gpu_memory: f64,
+    pub temperatures: Vec<(String, Series)>,
+    pub temperatures_critical: Vec<(String, f64)>,
+    pub total_memory: f64,
+    pub total_swap: f64,
+    /// Number of processes.
+    pub processes: Series,
+    /// Processes picked in the process list to be plotted.
+    pub watched: Vec<WatchedProcess>,
+    /// Number of most CPU hungry processes to record at each sample, 0 to
+    /// disable it.
+    pub top_count: usize,
+    /// Processes that were among the most CPU hungry ones within the
+    /// retention.
+    pub top_processes: Vec<WatchedProcess>,
+    /// Actions taken from the app, such as killing a process, with the
+    /// UNIX timestamp at which they happened.
+    pub events: Vec<(f64, String)>,
+    /// Alerts raised so far, oldest first. They are kept until cleared,
+    /// whatever the retention.
+    pub alerts: Vec<Alert>,
+    /// Metrics past their thresholds, so that an alert is raised only once
+    /// per level until they go back under it.
+    alerting: HashMap<&'static str, Level>,
+    /// Time of the latest sample, as a UNIX timestamp in seconds.
+    pub secs: f64,
+    /// Network and disk counters are cumulative, so rates are computed from
+    /// the difference with the previous sample.
+    last_sample: Option<Instant>,
+    last_cpu_times: Option<CpuTimes>,
+}
+
+/// Throughput of one network interface or disk, in bytes per second:
+/// received then transmitted for interfaces, read then written for disks.
+///
+/// Devices that went away keep being sampled as idle, so that all the
+/// devices have a sample at the same times.
+pub struct Throughput {
+    pub name: String,
+    pub rates: [Series; 2],
+    last_totals: Option<[u64; 2]>,
+}
+
+/// A metric that went past its threshold, or a watchdog rule that was
+/// triggered.
+pub struct Alert {
+    /// UNIX timestamp of when it was raised.
+    pub time: f64,
+    pub metric: String,
+    /// The value of the metric, formatted.
+    pub value: String,
+    /// The process using the most of the resource at the time.
+    pub process: Option<(Pid, String)>,
+    pub acknowledged: bool,
+}
+
+/// Number of alerts kept, the oldest ones being dropped first.
+const MAX_ALERTS: usize = 1000;
+
+/// Usage of a single process. Samples stop once the process exits.
+pub struct WatchedProcess {
+    pub pid: Pid,
+    pub name: String,
+    pub cpu: Series,
+    pub memory: Series,
+}
+
+impl History {
+    pub fn is_watched(&self, pid: Pid) -> bool {
+        self.watched.iter().any(|watched| watched.pid == pid)
+    }
+
+    pub fn watch(&mut self, pid: Pid, name: &str) {
+        if !self.is_watched(pid) {
+            self.watched.push(WatchedProcess {
+                pid,
+                name: name.to_string(),
+                cpu: Series::default(),
+                memory: Series::default(),
+            });
+        }
+    }
+
+    pub fn unwatch(&mut self, pid: Pid) {
+        self.watched.retain(|watched| watched.pid != pid);
+    }
+
+    /// Adds an event, to be shown as a marker on the graphs.
+    pub fn log_event(&mut self, label: String) {
+        self.events.push((unix_secs(), label));
+    }
+
+    pub fn record(
+        &mut self,
+        system: &System,
+        gpus: &Gpus,
+        retention: f64,
+        thresholds: &Thresholds,
+        temperature_unit: TemperatureUnit,
+    ) {
+        let now = Instant::now();
+        let secs = unix_secs();
+        self.cpu.push([secs, system.global_cpu_info().cpu_usage() as f64], retention);
+        let cpu_times = cpu_time::cpu_times();
+        if let (Some(times), Some(last)) = (cpu_times, self.last_cpu_times) {
+            let total = times.total.saturating_sub(last.total).max(1) as f64;
+            let shares = [
+                times.user.saturating_sub(last.user),
+                times.system.saturating_sub(last.system),
+                times.iowait.saturating_sub(last.iowait),
+                times.steal.saturating_sub(last.steal),
+            ];
+            for (series, share) in self.cpu_time.iter_mut().zip(shares) {
+                series.push([secs, share as f64 / total * 100.0], retention);
+            }
+        }
+        self.last_cpu_times = cpu_times;
+        self.cores.resize_with(system.cpus().len(), Default::default);
+        for (core, cpu) in self.cores.iter_mut().zip(system.cpus()) {
+            core.0 = cpu.name().to_string();
+            core.1.push([secs, cpu.cpu_usage() as f64], retention);
+        }
+        self.cores_frequency.resize_with(system.cpus().len(), Default::default);
+        for (core, cpu) in self.cores_frequency.iter_mut().zip(system.cpus()) {
+            core.0 = cpu.name().to_string();
+            core.1.push([secs, cpu.frequency() as f64], retention);
+        }
+        let frequency = system.cpus().iter().map(|cpu| cpu.frequency() as f64).sum::<f64>()
+            / system.cpus().len().max(1) as f64;
+        self.frequency.push([secs, frequency], retention);
+        let load_average = system.load_average();
+        self.load_average[0].push([secs, load_average.one], retention);
+        self.load_average[1].push([secs, load_average.five], retention);
+        self.load_average[2].push([secs, load_average.fifteen], retention);
+        self.memory.push([secs, system.used_memory() as f64], retention);
+        let cache = system.available_memory().saturating_sub(system.free_memory());
+        self.cache.push([secs, cache as f64], retention);
+        self.free.push([secs, system.free_memory() as f64], retention);
+        self.swap.push([secs, system.used_swap() as f64], retention);
+        let elapsed = self.last_sample.map(|time| now.duration_since(time).as_secs_f64());
+        let network = system
+            .networks()
+            .iter()
+            .map(|(name, network)| (name.clone(), [network.total_received(), network.total_transmitted()]))
+            .collect();
+        record_throughputs(&mut self.interfaces, network, elapsed, secs, retention);
+        record_throughputs(&mut self.disks, disks::disk_io(), elapsed, secs, retention);
+        self.last_sample = Some(now);
+        let gpu_infos = gpus.gpus();
+        self.gpus.resize_with(gpu_infos.len(), Default::default);
+        self.gpus_memory.resize_with(gpu_infos.len(), Default::default);
+        self.total_gpu_memory = 0.0;
+        for (index, gpu) in gpu_infos.into_iter().enumerate() {
+            self.gpus[index].0 = gpu.name.clone();
+            self.gpus[index].1.push([secs, gpu.utilization as f64], retention);
+            self.gpus_memory[index].0 = format!("{} VRAM", gpu.name);
+            self.gpus_memory[index].1.push([secs, gpu.memory_used as f64], retention);
+            self.total_gpu_memory = self.total_gpu_memory.max(gpu.memory_total as f64);
+        }
+        self.temperatures.resize_with(system.components().len(), Default::default);
+        for (temperature, component) in self.temperatures.iter_mut().zip(system.components()) {
+            temperature.0 = component.label().to_string();
+            temperature.1.push([secs, component.temperature() as f64], retention);
+        }
+        self.temperatures_critical = system
+            .components()
+            .iter()
+            .filter_map(|component| {
+                component
+                    .critical()
+                    .map(|critical| (format!("{} critical", component.label()), critical as f64))
+            })
+            .collect();
+        self.processes.push([secs, system.processes().len() as f64], retention);
+        for watched in &mut self.watched {
+            if let Some(process) = system.process(watched.pid) {
+                watched.cpu.push([secs, process.cpu_usage() as f64], retention);
+                watched.memory.push([secs, process.memory() as f64], retention);
+            }
+        }
+        let mut top = system.processes().values().collect::<Vec<_>>();
+        top.sort_by(|a, b| b.cpu_usage().total_cmp(&a.cpu_usage()));
+        for process in top.into_iter().take(self.top_count) {
+            if !self.top_processes.iter().any(|top| top.pid == process.pid()) {
+                self.top_processes.push(WatchedProcess {
+                    pid: process.pid(),
+                    name: process.name().to_string(),
+                    cpu: Series::default(),
+                    memory: Series::default(),
+                });
+            }
+        }
+        for top in &mut self.top_processes {
+            if let Some(process) = system.process(top.pid) {
+                top.cpu.push([secs, process.cpu_usage() as f64], retention);
+            }
+        }
+        self.top_processes.retain(|top| top.cpu.iter().last().map_or(false, |[x, _]| x >= secs - retention));
+        if self.top_count == 0 {
+            self.top_processes.clear();
+        }
+        self.events.retain(|(x, _)| *x >= secs - retention);
+        self.total_memory = system.total_memory() as f64;
+        self.total_swap = system.total_swap() as f64;
+        self.secs = secs;
+        self.check_alerts(system, thresholds, temperature_unit);
+    }
+
+    /// Raises an alert for the metrics that just went past one of their
+    /// thresholds.
+    fn check_alerts(&mut self, system: &System, thresholds: &Thresholds, temperature_unit: TemperatureUnit) {
+        for reading in readings(system, thresholds, temperature_unit) {
+            let Some(level) = reading.level() else {
+                self.alerting.remove(reading.metric);
+                continue;
+            };
+            if self.alerting.insert(reading.metric, level) != Some(level) {
+                self.raise_alert(
+                    format!("{} ({})", reading.metric, level.label()),
+                    reading.format(),
+                    reading.process(system),
+                );
+            }
+        }
+    }
+
+    pub fn raise_alert(&mut self, metric: String, value: String, process: Option<(Pid, String)>) {
+        if self.alerts.len() == MAX_ALERTS {
+            self.alerts.remove(0);
+        }
+        self.alerts.push(Alert {
+            time: unix_secs(),
+            metric,
+            value,
+            process,
+            acknowledged: false,
+        });
+    }
+}
+
+/// The current value of a metric watched by the thresholds.
+pub struct Reading {
+    pub metric: &'static str,
+    pub value: f32,
+    pub unit: &'static str,
+    pub threshold: Threshold,
+    /// How the processes use the resource, to name the top one.
+    usage: Option<fn(&sysinfo::Process) -> f64>,
+}
+
+impl Reading {
+    pub fn level(&self) -> Option<Level> {
+        self.threshold.level(self.value)
+    }
+
+    pub fn format(&self) -> String {
+        format!("{:.0}{}", self.value, self.unit)
+    }
+
+    /// The process using the most of the resource, if it is one processes
+    /// use.
+    pub fn process(&self, system: &System) -> Option<(Pid, String)> {
+        self.usage.and_then(|usage| top_process(system, usage))
+    }
+}
+
+/// The metrics watched by the thresholds: usages in percent, and the
+/// temperature of the hottest sensor in the chosen unit. The fullest disk
+/// stands for the disks.
+pub fn readings(system: &System, thresholds: &Thresholds, temperature_unit: TemperatureUnit) -> Vec<Reading> {
+    let percent = |used: u64, total: u64| used as f32 / total.max(1) as f32 * 100.0;
+    let by_cpu: fn(&sysinfo::Process) -> f64 = |process| process.cpu_usage() as f64;
+    let by_memory: fn(&sysinfo::Process) -> f64 = |process| process.memory() as f64;
+    let mut readings = vec![
+        Reading {
+            metric: "CPU usage",
+            value: system.global_cpu_info().cpu_usage(),
+            unit: "%",
+            threshold: thresholds.cpu,
+            usage: Some(by_cpu),
+        },
+        Reading {
+            metric: "Memory usage",
+            value: percent(system.used_memory(), system.total_memory()),
+            unit: "%",
+            threshold: thresholds.memory,
+            usage: Some(by_memory),
+        },
+    ];
+    if system.total_swap() > 0 {
+        readings.push(Reading {
+            metric: "Swap usage",
+            value: percent(system.used_swap(), system.total_swap()),
+            unit: "%",
+            threshold: thresholds.swap,
+            usage: Some(by_memory),
+        });
+    }
+    let fullest = system
+        .disks()
+        .iter()
+        .filter(|disk| disk.total_space() > 0)
+        .map(|disk| percent(disk.total_space() - disk.available_space(), disk.total_space()))
+        .max_by(|a, b| a.total_cmp(b));
+    if let Some(value) = fullest {
+        readings.push(Reading {
+            metric: "Disk space",
+            value,
+            unit: "%",
+            threshold: thresholds.disk,
+            usage: None,
+        });
+    }
+    let hottest = system
+        .components()
+        .iter()
+        .map(|component| component.temperature())
+        .max_by(|a, b| a.total_cmp(b));
+    if let Some(value) = hottest {
+        readings.push(Reading {
+            metric: "Temperature",
+            value: temperature_unit.convert(value as f64) as f32,
+            unit: match temperature_unit {
+                TemperatureUnit::Celsius => " °C",
+                TemperatureUnit::Fahrenheit => " °F",
+            },
+            threshold: temperature_unit.convert_threshold(thresholds.temperature),
+            usage: Some(by_cpu),
+        });
+    }
+    readings
+}
+
+/// The process using the most of a resource, with its name.
+pub fn top_process(system: &System, usage: fn(&sysinfo::Process) -> f64) -> Option<(Pid, String)> {
+    system
+        .processes()
+        .values()
+        .max_by(|a, b| usage(a).total_cmp(&usage(b)))
+        .map(|process| (process.pid(), process.name().to_string()))
+}
+
+fn unix_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs_f64())
+        .unwrap_or_default()
+}
+
+/// Turns the cumulative byte counters of some devices into rates.
+fn record_throughputs(
+    throughputs: &mut Vec<Throughput>,
+    totals: Vec<(String, [u64; 2])>,
+    elapsed: Option<f64>,
+    secs: f64,
+    retention: f64,
+) {
+    for (name, _) in &totals {
+        if !throughputs.iter().any(|throughput| &throughput.name == name) {
+            throughputs.push(Throughput {
+                name: name.clone(),
+                rates: Default::default(),
+                last_totals: None,
+            });
+        }
+    }
+    for throughput in throughputs {
+        let totals = totals
+            .iter()
+            .find(|(name, _)| *name == throughput.name)
+            .map(|(_, totals)| *totals);
+        if let Some(elapsed) = elapsed {
+            for (index, rate) in throughput.rates.iter_mut().enumerate() {
+                let value = match (totals, throughput.last_totals) {
+                    (Some(totals), Some(last_totals)) => {
+                        totals[index].saturating_sub(last_totals[index]) as f64 / elapsed
+                    }
+                    _ => 0.0,
+                };
+                rate.push([secs, value], retention);
+            }
+        }
+        throughput.last_totals = totals;
+    }
+}
@@ -0,0 +1,81 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use sysinfo::System;
+use zbus::{
+    blocking::{Connection, Proxy},
+    zvariant::Value,
+};
+
+use crate::{
+    metrics::readings,
+    settings::{Level, Notifications, TemperatureUnit, Thresholds},
+};
+
+/// Sends a desktop notification when a metric stays past its critical
+/// threshold long enough, once until it goes back under it.
+#[derive(Default)]
+pub struct Notifier {
+    /// When each metric went past its threshold, and whether it was
+    /// notified since.
+    past: HashMap<&'static str, (Instant, bool)>,
+    connection: Option<Connection>,
+}
+
+impl Notifier {
+    pub fn check(
+        &mut self,
+        system: &System,
+        thresholds: &Thresholds,
+        temperature_unit: TemperatureUnit,
+        settings: &Notifications,
+    ) {
+        if !settings.enabled {
+            self.past.clear();
+            return;
+        }
+        let mut messages = vec![];
+        for reading in readings(system, thresholds, temperature_unit) {
+            if reading.level() != Some(Level::Critical) {
+                self.past.remove(reading.metric);
+                continue;
+            }
+            let (since, notified) = self.past.entry(reading.metric).or_insert((Instant::now(), false));
+            if *notified || since.elapsed() < Duration::from_secs(settings.duration_secs) {
+                continue;
+            }
+            *notified = true;
+            let mut body = format!("{} for more than {} s.", reading.format(), settings.duration_secs);
+            if let Some((pid, name)) = reading.process(system) {
+                body += &format!(" Top process: {name} ({pid}).");
+            }
+            messages.push((format!("{} above {:.0}{}", reading.metric, reading.threshold.critical, reading.unit), body));
+        }
+        for (summary, body) in messages {
+            if let Err(error) = self.notify(&summary, &body) {
+                eprintln!("Could not send a notification: {error}");
+            }
+        }
+    }
+
+    pub fn notify(&mut self, summary: &str, body: &str) -> zbus::Result<()> {
+        if self.connection.is_none() {
+            self.connection = Some(Connection::session()?);
+        }
+        let proxy = Proxy::new(
+            self.connection.as_ref().unwrap(),
+            "org.freedesktop.Notifications",
+            "/org/freedesktop/Notifications",
+            "org.freedesktop.Notifications",
+        )?;
+        let actions: Vec<&str> = vec![];
+        let hints: HashMap<&str, Value<'_>> = HashMap::new();
+        proxy.call_method(
+            "Notify",
+            &("Task Manager", 0u32, "utilities-system-monitor", summary, body, actions, hints, -1i32),
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+use std::{
+    collections::HashSet,
+    fs,
+    sync::{Arc, Mutex},
+};
+
+use egui_extras::{Column, TableBuilder};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+/// A file a process has open, like a line of `lsof`.
+pub struct OpenFile {
+    pub pid: Pid,
+    /// The file descriptor, or what else holds the file: `cwd` for the
+    /// working directory, `txt` for the executable and `mem` for mapped
+    /// files.
+    pub fd: String,
+    pub path: String,
+}
+
+/// The files open by all the processes that can be inspected, which are
+/// only those of the current user unless running as root.
+pub fn open_files() -> Vec<OpenFile> {
+    let Ok(processes) = fs::read_dir("/proc") else {
+        return vec![];
+    };
+    let mut files = vec![];
+    for process in processes.flatten() {
+        let Some(pid) = process.file_name().to_str().and_then(|pid| pid.parse::<u32>().ok()) else {
+            continue;
+        };
+        let pid = Pid::from_u32(pid);
+        let dir = process.path();
+        for (fd, link) in [("cwd", "cwd"), ("txt", "exe")] {
+            if let Ok(path) = fs::read_link(dir.join(link)) {
+                files.push(OpenFile {
+                    pid,
+                    fd: fd.to_string(),
+                    path: path.to_string_lossy().to_string(),
+                });
+            }
+        }
+        if let Ok(fds) = fs::read_dir(dir.join("fd")) {
+            for fd in fds.flatten() {
+                if let Ok(path) = fs::read_link(fd.path()) {
+                    files.push(OpenFile {
+                        pid,
+                        fd: fd.file_name().to_string_lossy().to_string(),
+                        path: path.to_string_lossy().to_string(),
+                    });
+                }
+            }
+        }
+        // Libraries and other mapped files keep a mount busy too.
+        if let Ok(maps) = fs::read_to_string(dir.join("maps")) {
+            let mut mapped = HashSet::new();
+            for path in maps.lines().filter_map(|line| line.split_whitespace().nth(5)) {
+                if path.starts_with('/') && mapped.insert(path) {
+                    files.push(OpenFile {
+                        pid,
+                        fd: "mem".to_string(),
+                        path: path.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    files
+}
+
+#[derive(Default)]
+pub struct OpenFilesState {
+    files: Vec<OpenFile>,
+    loaded: bool,
+    search: String,
+    /// Whether sockets, pipes and other files without a path are listed.
+    show_special: bool,
+}
+
+impl OpenFilesState {
+    /// Lists the open files matching a path. Returns the process to show in
+    /// the process list, if one was clicked.
+    pub fn open_files_view(
+        &mut self,
+        ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        system: Arc<Mutex<System>>,
+    ) -> Option<Pid> {
+        // Going through all the processes is slow, so it is only done on
+        // demand.
+        if !self.loaded {
+            self.files = open_files();
+            self.loaded = true;
+        }
+        let system = system.lock().unwrap();
+        let mut jump = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Path:");
+                ui.text_edit_singleline(&mut self.search)
+                    .on_hover_text("Lists the files whose path contains this, such as a mount point");
+                ui.checkbox(&mut self.show_special, "Sockets and pipes");
+                if ui.button("Refresh").clicked() {
+                    self.loaded = false;
+                }
+            });
+            let files = self
+                .files
+                .iter()
+                .filter(|file| self.show_special || file.path.starts_with('/'))
+                .filter(|file| file.path.contains(&self.search))
+                .collect::<Vec<_>>();
+            ui.label(format!("{} open files", files.len()));
+
+            let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+            TableBuilder::new(ui)
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .column(Column::auto().at_least(192.0))
+                .column(Column::auto().at_least(48.0))
+                .column(Column::remainder())
+                .min_scrolled_height(0.0)
+                .header(20.0, |mut header| {
+                    for title in ["process", "fd", "path"] {
+                        header.col(|ui| {
+                            ui.strong(title);
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(text_height, files.len(), |index, mut row| {
+                        let file = files[index];
+                        row.col(|ui| {
+                            let name = system.process(file.pid).map_or("", |process| process.name());
+                            if ui
+                                .link(format!("{name} ({})", file.pid))
+                                .on_hover_text("Show in the process list")
+                                .clicked()
+                            {
+                                jump = Some(file.pid);
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(&file.fd);
+                        });
+                        row.col(|ui| {
+                            ui.label(&file.path);
+                        });
+                    });
+                });
+        });
+        jump
+    }
+}
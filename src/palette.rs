@@ -0,0 +1,132 @@
+use egui::{Align2, Key, Modifiers, ScrollArea, TextEdit};
+
+use crate::{app::ViewKind, help::HelpPage, keybindings::Action, process_list::QuickFilter};
+
+/// Something the command palette can do.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Open(ViewKind),
+    Run(Action),
+    ToggleRegex,
+    ToggleLabelSearch,
+    ToggleCaseSensitive,
+    QuickFilter(QuickFilter),
+    Help(HelpPage),
+}
+
+impl Command {
+    /// Every command, in the order they are listed before typing.
+    fn all() -> Vec<Command> {
+        let mut commands = ViewKind::ALL.iter().map(|kind| Command::Open(*kind)).collect::<Vec<_>>();
+        // The actions switching views are already covered above.
+        commands.extend([Action::KillSelected, Action::FocusSearch, Action::Refresh, Action::Quit].map(Command::Run));
+        commands.extend([Command::ToggleRegex, Command::ToggleLabelSearch, Command::ToggleCaseSensitive]);
+        commands.extend(QuickFilter::ALL.map(Command::QuickFilter));
+        commands.extend(HelpPage::ALL.map(Command::Help));
+        commands
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Command::Open(kind) => format!("Open {}", kind.label()),
+            Command::Run(action) => action.label().to_string(),
+            Command::ToggleRegex => "Toggle regex search".to_string(),
+            Command::ToggleLabelSearch => "Toggle label search".to_string(),
+            Command::ToggleCaseSensitive => "Toggle case sensitive search".to_string(),
+            Command::QuickFilter(filter) => format!("Toggle quick filter: {}", filter.label()),
+            Command::Help(page) => format!("Help: {}", page.label()),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PaletteState {
+    query: String,
+    /// Index of the highlighted command among the matching ones.
+    selected: usize,
+}
+
+/// Scores how well a query matches a text, if all its characters appear in
+/// it in order. Consecutive characters and the ones starting a word score
+/// higher.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text = text.to_lowercase().chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut start = 0;
+    let mut previous = None;
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let index = start + text[start..].iter().position(|t| *t == c)?;
+        score += 1;
+        if index > 0 && previous == Some(index - 1) {
+            score += 5;
+        }
+        if index == 0 || !text[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(index);
+        start = index + 1;
+    }
+    Some(score)
+}
+
+/// Shows the palette while it is open and returns the command picked, which
+/// closes it, as does Escape.
+pub fn palette_window(ctx: &egui::Context, palette: &mut Option<PaletteState>) -> Option<Command> {
+    let state = palette.as_mut()?;
+    let mut commands = Command::all()
+        .into_iter()
+        .filter_map(|command| Some((fuzzy_score(&state.query, &command.label())?, command)))
+        .collect::<Vec<_>>();
+    // The sort is stable, so equal scores keep their order.
+    commands.sort_by_key(|(score, _)| -score);
+    let (up, down, enter, escape) = ctx.input_mut(|input| {
+        (
+            input.consume_key(Modifiers::NONE, Key::ArrowUp),
+            input.consume_key(Modifiers::NONE, Key::ArrowDown),
+            input.consume_key(Modifiers::NONE, Key::Enter),
+            input.consume_key(Modifiers::NONE, Key::Escape),
+        )
+    });
+    if escape {
+        *palette = None;
+        return None;
+    }
+    if up {
+        state.selected = state.selected.saturating_sub(1);
+    }
+    if down {
+        state.selected += 1;
+    }
+    state.selected = state.selected.min(commands.len().saturating_sub(1));
+    let mut picked = if enter { commands.get(state.selected).map(|(_, command)| *command) } else { None };
+    egui::Window::new("Command palette")
+        .title_bar(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_TOP, [0.0, 40.0])
+        .show(ctx, |ui| {
+            let response = ui.add(TextEdit::singleline(&mut state.query).hint_text("Type a command").desired_width(400.0));
+            response.request_focus();
+            if response.changed() {
+                state.selected = 0;
+            }
+            ui.separator();
+            ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                if commands.is_empty() {
+                    ui.weak("No matching command");
+                }
+                for (index, (_, command)) in commands.iter().enumerate() {
+                    let response = ui.selectable_label(index == state.selected, command.label());
+                    if index == state.selected && (up || down) {
+                        response.scroll_to_me(None);
+                    }
+                    if response.clicked() {
+                        picked = Some(*command);
+                    }
+                }
+            });
+        });
+    if picked.is_some() {
+        *palette = None;
+    }
+    picked
+}
@@ -19,6 +19,7 @@ use nom::IResult;
 /// Parse a unicode sequence, of the form u{XXXX}, where XXXX is 1 to 6
 /// hexadecimal numerals. We will combine this later with parse_escaped_char
 /// to parse sequences like \u{00AC}.
+#[allow(clippy::redundant_closure)]
 fn parse_unicode<'a, E>(input: &'a str) -> IResult<&'a str, char, E>
 where
     E: ParseError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
@@ -173,9 +174,9 @@ fn parse_owner_label<'a, E>(input: &'a str) -> IResult<&'a str, String, E>
 where
     E: ParseError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
 {
-    map_res(
-        preceded(complete_tag("owner:"), alphanumeric1),
-        |s: &str| Ok(String::from(s)),
+    preceded(
+        complete_tag("owner:"),
+        alt((parse_string, map(alphanumeric1, String::from))),
     )(input)
 }
 
@@ -0,0 +1,150 @@
+use std::sync::{Arc, Mutex};
+
+use egui::{
+    plot::{Line, Plot},
+    Sense,
+};
+
+use crate::{
+    graphs::GraphKind,
+    metrics::History,
+    series,
+    settings::{Level, Settings},
+};
+
+/// Seconds of history shown in the sparklines.
+const SPARKLINE_SECS: f64 = 60.0;
+
+/// A compact gauge of the performance view.
+struct Gauge {
+    title: String,
+    value: String,
+    /// How full the resource is, for the ones that have a maximum.
+    fraction: Option<f32>,
+    /// How far the resource is past its thresholds, for the ones that have
+    /// some.
+    level: Option<Level>,
+    points: Vec<[f64; 2]>,
+    max_y: f64,
+    graph: GraphKind,
+}
+
+/// Shows the main resources at a glance. Returns the graph to show in the
+/// graphs view, if a gauge was clicked.
+pub fn performance_view(
+    ctx: &egui::Context,
+    _frame: &mut eframe::Frame,
+    history: Arc<Mutex<History>>,
+    settings: Arc<Mutex<Settings>>,
+) -> Option<GraphKind> {
+    let gauges = gauges(&history.lock().unwrap(), &settings.lock().unwrap());
+    let mut jump = None;
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.horizontal_wrapped(|ui| {
+            for (index, gauge) in gauges.into_iter().enumerate() {
+                let group = ui
+                    .group(|ui| {
+                        ui.set_width(220.0);
+                        ui.strong(&gauge.title);
+                        match gauge.level {
+                            Some(level) => ui.colored_label(level.color(), &gauge.value),
+                            None => ui.label(&gauge.value),
+                        };
+                        if let Some(fraction) = gauge.fraction {
+                            let mut bar = egui::ProgressBar::new(fraction).desired_width(220.0);
+                            if let Some(level) = gauge.level {
+                                bar = bar.fill(level.color());
+                            }
+                            ui.add(bar);
+                        }
+                        Plot::new(("sparkline", index))
+                            .height(48.0)
+                            .width(220.0)
+                            .show_axes([false, false])
+                            .show_x(false)
+                            .show_y(false)
+                            .allow_drag(false)
+                            .allow_zoom(false)
+                            .allow_scroll(false)
+                            .allow_boxed_zoom(false)
+                            .include_y(0.0)
+                            .include_y(gauge.max_y)
+                            .show(ui, |plot_ui| plot_ui.line(Line::new(gauge.points).fill(0.0)))
+                            .response
+                    });
+                let clicked = ui
+                    .interact(group.response.rect, group.response.id.with("gauge"), Sense::click())
+                    .on_hover_text(format!("Show the {} graph", gauge.graph.title().to_lowercase()))
+                    .clicked();
+                // The sparkline takes the clicks made on it.
+                if clicked || group.inner.clicked() {
+                    jump = Some(gauge.graph);
+                }
+            }
+        });
+    });
+    jump
+}
+
+fn gauges(history: &History, settings: &Settings) -> Vec<Gauge> {
+    let min_x = history.secs - SPARKLINE_SECS;
+    let recent = |points: Vec<[f64; 2]>| points.into_iter().filter(|[x, _]| *x >= min_x).collect::<Vec<_>>();
+    let latest = |points: &[[f64; 2]]| points.last().map_or(0.0, |[_, y]| *y);
+    let (gib, gib_name) = settings.byte_units.unit(3);
+    let (mib, mib_name) = settings.byte_units.unit(2);
+    let throughput = |points: &[[f64; 2]]| points.iter().fold(1.0, |max: f64, [_, y]| max.max(*y));
+
+    let mut gauges = vec![];
+    let cpu = recent(history.cpu.points());
+    gauges.push(Gauge {
+        title: "CPU".to_string(),
+        value: format!("{:.0}%", latest(&cpu)),
+        fraction: Some(latest(&cpu) as f32 / 100.0),
+        level: settings.thresholds.cpu.level(latest(&cpu) as f32),
+        points: cpu,
+        max_y: 100.0,
+        graph: GraphKind::Cpu,
+    });
+
+    let memory = recent(history.memory.points());
+    let used = latest(&memory);
+    gauges.push(Gauge {
+        title: "Memory".to_string(),
+        value: format!("{:.1} / {:.1} {}", used / gib, history.total_memory / gib, gib_name),
+        fraction: Some((used / history.total_memory.max(1.0)) as f32),
+        level: settings.thresholds.memory.level((used / history.total_memory.max(1.0) * 100.0) as f32),
+        points: memory,
+        max_y: history.total_memory,
+        graph: GraphKind::Memory,
+    });
+
+    for (title, devices, graph) in [
+        ("Disk", &history.disks, GraphKind::DiskIo),
+        ("Network", &history.interfaces, GraphKind::Network),
+    ] {
+        let points = recent(series::sum_points(devices.iter().flat_map(|device| &device.rates)));
+        gauges.push(Gauge {
+            title: title.to_string(),
+            value: format!("{:.1} {}/s", latest(&points) / mib, mib_name),
+            fraction: None,
+            level: None,
+            max_y: throughput(&points),
+            points,
+            graph,
+        });
+    }
+
+    for (name, series) in &history.gpus {
+        let points = recent(series.points());
+        gauges.push(Gauge {
+            title: name.clone(),
+            value: format!("{:.0}%", latest(&points)),
+            fraction: Some(latest(&points) as f32 / 100.0),
+            level: None,
+            points,
+            max_y: 100.0,
+            graph: GraphKind::Gpu,
+        });
+    }
+    gauges
+}
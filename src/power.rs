@@ -0,0 +1,200 @@
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use egui::ProgressBar;
+#[cfg(feature = "systemd")]
+use sysinfo::PidExt;
+use sysinfo::Pid;
+
+use crate::settings::Settings;
+
+/// A battery, from `/sys/class/power_supply`.
+pub struct Battery {
+    pub name: String,
+    /// Charge, in percent.
+    pub capacity: Option<f32>,
+    /// Such as "Charging" or "Discharging".
+    pub status: String,
+    /// Energy the battery holds when full, compared to when it was new.
+    pub health: Option<f32>,
+    /// Power drawn from or put into the battery, in watts.
+    pub power: Option<f32>,
+    /// Time until the battery is empty, or full when charging.
+    pub time_remaining: Option<Duration>,
+}
+
+pub fn batteries() -> Vec<Battery> {
+    let Ok(supplies) = fs::read_dir("/sys/class/power_supply") else {
+        return vec![];
+    };
+    let mut batteries = supplies
+        .flatten()
+        .filter(|supply| fs::read_to_string(supply.path().join("type")).map_or(false, |kind| kind.trim() == "Battery"))
+        .map(|supply| battery(&supply.path()))
+        .collect::<Vec<_>>();
+    batteries.sort_by(|a, b| a.name.cmp(&b.name));
+    batteries
+}
+
+fn battery(path: &Path) -> Battery {
+    let read = |file: &str| fs::read_to_string(path.join(file)).ok().map(|value| value.trim().to_string());
+    let number = |file: &str| read(file).and_then(|value| value.parse::<f64>().ok());
+    // Some batteries report charges in µAh and currents in µA instead of
+    // energies in µWh and powers in µW, which the voltage converts.
+    let voltage = number("voltage_now").map(|microvolts| microvolts / 1e6);
+    let energy = |name: &str| {
+        number(&format!("energy_{name}"))
+            .or_else(|| Some(number(&format!("charge_{name}"))? * voltage?))
+            .map(|microwatt_hours| microwatt_hours / 1e6)
+    };
+    let (now, full, design) = (energy("now"), energy("full"), energy("full_design"));
+    let power = number("power_now")
+        .or_else(|| Some(number("current_now")? * voltage?))
+        .map(|microwatts| microwatts / 1e6)
+        .filter(|watts| *watts > 0.0);
+    let status = read("status").unwrap_or_default();
+    let hours = match (status.as_str(), now, full, power) {
+        ("Discharging", Some(now), _, Some(power)) => Some(now / power),
+        ("Charging", Some(now), Some(full), Some(power)) => Some((full - now).max(0.0) / power),
+        _ => None,
+    };
+    Battery {
+        name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        capacity: number("capacity").map(|capacity| capacity as f32),
+        status,
+        health: full.zip(design).map(|(full, design)| (full / design * 100.0) as f32),
+        power: power.map(|watts| watts as f32),
+        time_remaining: hours.map(|hours| Duration::from_secs_f64(hours * 3600.0)),
+    }
+}
+
+/// The inhibitors listed by a thread.
+#[cfg(feature = "systemd")]
+type Inhibitors = Result<Vec<crate::services::Inhibitor>, String>;
+
+#[derive(Default)]
+pub struct PowerState {
+    batteries: Vec<Battery>,
+    #[cfg(feature = "systemd")]
+    inhibitors: Vec<crate::services::Inhibitor>,
+    #[cfg(feature = "systemd")]
+    inhibitors_error: Option<String>,
+    /// Whether a thread is asking logind for the inhibitors, as D-Bus calls
+    /// block.
+    #[cfg(feature = "systemd")]
+    listing: bool,
+    /// Set by the thread asking for them.
+    #[cfg(feature = "systemd")]
+    listed: Arc<Mutex<Option<Inhibitors>>>,
+    #[cfg(feature = "systemd")]
+    buses: crate::services::Buses,
+    refreshed: Option<Instant>,
+}
+
+impl PowerState {
+    /// Shows the batteries, and what keeps the machine from sleeping.
+    /// Returns the process to show in the process list, if one was clicked.
+    pub fn power_view(
+        &mut self,
+        ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        settings: Arc<Mutex<Settings>>,
+    ) -> Option<Pid> {
+        let interval = Duration::from_millis(settings.lock().unwrap().refresh_intervals.sensors_ms);
+        #[cfg(feature = "systemd")]
+        if let Some(listed) = self.listed.lock().unwrap().take() {
+            self.listing = false;
+            match listed {
+                Ok(inhibitors) => {
+                    self.inhibitors = inhibitors;
+                    self.inhibitors_error = None;
+                }
+                Err(error) => self.inhibitors_error = Some(error),
+            }
+        }
+        if self.refreshed.map_or(true, |time| time.elapsed() >= interval) {
+            self.batteries = batteries();
+            #[cfg(feature = "systemd")]
+            if !self.listing {
+                self.listing = true;
+                let listed = self.listed.clone();
+                let buses = self.buses.clone();
+                let ctx = ctx.clone();
+                std::thread::spawn(move || {
+                    *listed.lock().unwrap() = Some(buses.with(false, crate::services::inhibitors));
+                    ctx.request_repaint();
+                });
+            }
+            self.refreshed = Some(Instant::now());
+        }
+        ctx.request_repaint_after(interval);
+
+        #[cfg_attr(not(feature = "systemd"), allow(unused_mut))]
+        let mut jump = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                if self.batteries.is_empty() {
+                    ui.label("No battery");
+                }
+                for battery in &self.batteries {
+                    ui.heading(&battery.name);
+                    egui::Grid::new(("battery", &battery.name)).num_columns(2).show(ui, |ui| {
+                        ui.label("Charge");
+                        ui.add(ProgressBar::new(battery.capacity.unwrap_or(0.0) / 100.0).show_percentage());
+                        ui.end_row();
+                        ui.label("Status");
+                        ui.label(&battery.status);
+                        ui.end_row();
+                        if let Some(health) = battery.health {
+                            ui.label("Health");
+                            ui.label(format!("{health:.0}%"));
+                            ui.end_row();
+                        }
+                        if let Some(power) = battery.power {
+                            ui.label(if battery.status == "Charging" { "Charge rate" } else { "Discharge rate" });
+                            ui.label(format!("{power:.1} W"));
+                            ui.end_row();
+                        }
+                        if let Some(time) = battery.time_remaining {
+                            let minutes = time.as_secs() / 60;
+                            ui.label(if battery.status == "Charging" { "Until full" } else { "Remaining" });
+                            ui.label(format!("{}h {:02}m", minutes / 60, minutes % 60));
+                            ui.end_row();
+                        }
+                    });
+                    ui.separator();
+                }
+
+                ui.heading("Preventing sleep");
+                #[cfg(feature = "systemd")]
+                {
+                    if let Some(error) = &self.inhibitors_error {
+                        ui.label(format!("Could not list the inhibitors: {error}"));
+                    }
+                    egui::Grid::new("inhibitors").num_columns(4).striped(true).show(ui, |ui| {
+                        for inhibitor in &self.inhibitors {
+                            if ui
+                                .link(format!("{} ({})", inhibitor.who, inhibitor.pid))
+                                .on_hover_text("Show in the process list")
+                                .clicked()
+                            {
+                                jump = Some(Pid::from_u32(inhibitor.pid));
+                            }
+                            ui.label(&inhibitor.what);
+                            ui.label(&inhibitor.mode);
+                            ui.label(&inhibitor.why);
+                            ui.end_row();
+                        }
+                    });
+                }
+                #[cfg(not(feature = "systemd"))]
+                ui.label("Needs the systemd feature, to ask logind.");
+            });
+        });
+        jump
+    }
+}
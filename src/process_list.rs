@@ -1,25 +1,107 @@
 use std::sync::{Mutex, Arc};
 
 use arboard::Clipboard;
-use egui::{Label, RichText, ScrollArea, Sense};
+use egui::{Key, Label, Modifiers, RichText, ScrollArea, Sense};
 use egui_extras::{Column, TableBuilder};
 use nom::error::VerboseError;
 use regex::Regex;
-use sysinfo::{Pid, Process, ProcessExt, Signal, System, SystemExt, UserExt};
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, Process, ProcessExt, ProcessStatus, Signal, System, SystemExt, Uid, UserExt};
 
-use crate::parse_labels::{self, Labels};
+use crate::{
+    format::format_time,
+    metrics::History,
+    parse_labels::{self, Labels},
+    settings::{ByteUnits, Density, Settings, TableStyle},
+};
 
 pub struct ProcessListState {
     search: String,
     regex: bool,
     label_search: bool,
-    first: bool,
+    /// Whether the search box is to take the focus. It does not on its own,
+    /// so that the keys of the process list work from the start.
+    focus_search: bool,
     sort: Columns,
     order: Order,
     case_sensitive: bool,
+    quick_filters: Vec<QuickFilter>,
+    /// Kill waiting for the user to confirm it.
+    pending_kill: Option<Kill>,
+    /// The process last clicked or moved to with the arrow keys.
+    selected: Option<Pid>,
+    /// Where the selection extended with Shift starts, the processes between
+    /// it and `selected` being selected too.
+    anchor: Option<Pid>,
+    /// Process whose details are shown in a window.
+    details: Option<Pid>,
+    /// Set by the keyboard shortcut, handled on next frame.
+    kill_selected: bool,
+    /// Set when a process is selected from another view, to scroll to it.
+    scroll_to_selected: bool,
+    /// Processes picked from another view, such as the ones of a container,
+    /// with what they are.
+    only: Option<(String, Vec<Pid>)>,
 }
 
+/// A signal to send to some processes.
+pub struct Kill {
+    pids: Vec<Pid>,
+    signal: Signal,
+    /// The processes, as shown to the user, such as "firefox (1234)".
+    description: String,
+    scope: KillScope,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KillScope {
+    Single,
+    /// A process and its descendants.
+    Tree,
+    /// All the processes matching the search.
+    All,
+    /// The processes selected in the table.
+    Selection,
+}
+
+/// One-click filters shown as chips above the table. They are combined with
+/// each other and with the text search.
 #[derive(Clone, Copy, PartialEq, Eq)]
+pub enum QuickFilter {
+    HighCpu,
+    HighMemory,
+    Mine,
+    Zombies,
+}
+
+impl QuickFilter {
+    pub const ALL: [QuickFilter; 4] = [
+        QuickFilter::HighCpu,
+        QuickFilter::HighMemory,
+        QuickFilter::Mine,
+        QuickFilter::Zombies,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            QuickFilter::HighCpu => "CPU > 25%",
+            QuickFilter::HighMemory => "Mem > 1 GB",
+            QuickFilter::Mine => "My processes",
+            QuickFilter::Zombies => "Zombies",
+        }
+    }
+
+    fn matches(&self, process: &Process, current_user: Option<&Uid>) -> bool {
+        match self {
+            QuickFilter::HighCpu => process.cpu_usage() > 25.0,
+            QuickFilter::HighMemory => process.memory() > 1_000_000_000,
+            QuickFilter::Mine => current_user.is_some() && process.user_id() == current_user,
+            QuickFilter::Zombies => process.status() == ProcessStatus::Zombie,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Order {
     Asc,
     Desc,
@@ -36,28 +118,192 @@ impl std::ops::Not for Order {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Columns {
     Pid,
     Owner,
     Name,
 }
 
+impl Columns {
+    pub const ALL: [Columns; 3] = [Columns::Pid, Columns::Owner, Columns::Name];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Columns::Pid => "pid",
+            Columns::Owner => "owner",
+            Columns::Name => "name",
+        }
+    }
+}
+
 impl Default for ProcessListState {
     fn default() -> Self {
         Self {
             search: String::new(),
             regex: false,
             label_search: false,
-            first: true,
+            focus_search: false,
             sort: Columns::Pid,
             order: Order::Asc,
             case_sensitive: false,
+            quick_filters: Vec::new(),
+            pending_kill: None,
+            selected: None,
+            anchor: None,
+            details: None,
+            kill_selected: false,
+            scroll_to_selected: false,
+            only: None,
         }
     }
 }
 
 impl ProcessListState {
+    /// The process list, initially sorted as chosen in the settings.
+    pub fn new(sort: Columns, order: Order) -> Self {
+        Self {
+            sort,
+            order,
+            ..Default::default()
+        }
+    }
+
+    pub fn focus_search(&mut self) {
+        self.focus_search = true;
+    }
+
+    pub fn kill_selected(&mut self) {
+        self.kill_selected = true;
+    }
+
+    pub fn toggle_regex(&mut self) {
+        self.regex = !self.regex;
+    }
+
+    pub fn toggle_label_search(&mut self) {
+        self.label_search = !self.label_search;
+    }
+
+    pub fn toggle_case_sensitive(&mut self) {
+        self.case_sensitive = !self.case_sensitive;
+    }
+
+    pub fn toggle_quick_filter(&mut self, filter: QuickFilter) {
+        if self.quick_filters.contains(&filter) {
+            self.quick_filters.retain(|f| *f != filter);
+        } else {
+            self.quick_filters.push(filter);
+        }
+    }
+
+    /// Shows only the processes of a user, through a label search.
+    pub fn search_owner(&mut self, owner: &str) {
+        self.label_search = true;
+        self.regex = false;
+        self.search = format!("owner:\"{owner}\"");
+    }
+
+    /// Searches the processes, with a label search if the search is a valid
+    /// one, and by name otherwise.
+    pub fn set_search(&mut self, search: String) {
+        self.label_search = matches!(
+            parse_labels::parse_input::<VerboseError<&str>>(&search),
+            Ok(("", labels)) if !labels.is_empty()
+        );
+        self.regex = false;
+        self.search = search;
+    }
+
+    /// Shows only some processes, until the filter is cleared.
+    pub fn show_only(&mut self, label: String, pids: Vec<Pid>) {
+        self.only = Some((label, pids));
+    }
+
+    pub fn select(&mut self, pid: Pid) {
+        self.selected = Some(pid);
+        self.anchor = Some(pid);
+        self.scroll_to_selected = true;
+    }
+
+    /// The selected processes among the shown ones, in their order.
+    fn selection(&self, processes: &[(&Pid, &Process)]) -> Vec<Pid> {
+        let position = |pid: Option<Pid>| processes.iter().position(|(other, _)| Some(**other) == pid);
+        match (position(self.anchor), position(self.selected)) {
+            (Some(anchor), Some(selected)) => processes[anchor.min(selected)..=anchor.max(selected)]
+                .iter()
+                .map(|(pid, _)| **pid)
+                .collect(),
+            (None, Some(selected)) => vec![*processes[selected].0],
+            _ => vec![],
+        }
+    }
+
+    /// Moves the selection with the arrow keys, extending it while Shift is
+    /// held, and opens the details of the selected process with Enter.
+    fn navigate(&mut self, ctx: &egui::Context, processes: &[(&Pid, &Process)]) {
+        let (up, down, shift, enter) = ctx.input_mut(|input| {
+            let shift = input.modifiers.shift;
+            let modifiers = if shift { Modifiers::SHIFT } else { Modifiers::NONE };
+            (
+                input.consume_key(modifiers, Key::ArrowUp),
+                input.consume_key(modifiers, Key::ArrowDown),
+                shift,
+                self.selected.is_some() && input.consume_key(Modifiers::NONE, Key::Enter),
+            )
+        });
+        if enter {
+            self.details = self.selected;
+        }
+        if !up && !down || processes.is_empty() {
+            return;
+        }
+        let index = match processes.iter().position(|(pid, _)| Some(**pid) == self.selected) {
+            Some(index) if up => index.saturating_sub(1),
+            Some(index) => (index + 1).min(processes.len() - 1),
+            None => 0,
+        };
+        self.selected = Some(*processes[index].0);
+        if !shift || self.anchor.is_none() {
+            self.anchor = self.selected;
+        }
+        self.scroll_to_selected = true;
+    }
+
+    /// Selects a clicked process, or extends the selection to it with Shift.
+    fn click(&mut self, ui: &egui::Ui, pid: Pid) {
+        self.selected = Some(pid);
+        if !ui.input(|input| input.modifiers.shift) || self.anchor.is_none() {
+            self.anchor = Some(pid);
+        }
+    }
+
     fn filtered_processes<'a>(&self, system: &'a System) -> Vec<(&'a Pid, &'a Process)> {
+        let mut processes = self.searched_processes(system);
+        if let Some((_, pids)) = &self.only {
+            processes.retain(|(pid, _)| pids.contains(pid));
+        }
+        if !self.quick_filters.is_empty() {
+            let current_user = sysinfo::get_current_pid()
+                .ok()
+                .and_then(|pid| system.process(pid))
+                .and_then(|process| process.user_id());
+            processes.retain(|(_, process)| {
+                self.quick_filters
+                    .iter()
+                    .all(|filter| filter.matches(process, current_user))
+            });
+        }
+        processes
+    }
+
+    #[allow(
+        clippy::comparison_to_empty,
+        clippy::into_iter_on_ref,
+        clippy::needless_borrow,
+        clippy::redundant_pattern_matching
+    )]
+    fn searched_processes<'a>(&self, system: &'a System) -> Vec<(&'a Pid, &'a Process)> {
         let Self {
             search,
             regex,
@@ -101,13 +347,7 @@ impl ProcessListState {
                                 processes = processes
                                     .into_iter()
                                     .filter(|(_, process)| {
-                                        sensitiveness(
-                                            system
-                                                .get_user_by_id(process.user_id().unwrap())
-                                                .unwrap()
-                                                .name(),
-                                        )
-                                        .contains(&sensitiveness(&name))
+                                        sensitiveness(owner_name(system, process)).contains(&sensitiveness(&name))
                                     })
                                     .collect::<Vec<_>>();
                             }
@@ -164,21 +404,11 @@ impl ProcessListState {
             Columns::Owner => {
                 if self.order == Order::Asc {
                     processes.sort_by_key(|(_, process)| {
-                        sensitiveness(
-                            system
-                                .get_user_by_id(process.user_id().unwrap())
-                                .unwrap()
-                                .name(),
-                        )
+                        sensitiveness(owner_name(system, process))
                     });
                 } else {
                     processes.sort_by_key(|(_, process)| {
-                        std::cmp::Reverse(sensitiveness(
-                            system
-                                .get_user_by_id(process.user_id().unwrap())
-                                .unwrap()
-                                .name(),
-                        ))
+                        std::cmp::Reverse(sensitiveness(owner_name(system, process)))
                     });
                 }
             }
@@ -195,13 +425,20 @@ impl ProcessListState {
         processes
     }
 
-    fn menu_bar(&mut self, ui: &mut egui::Ui, processes: &Vec<(&Pid, &Process)>) {
+    fn menu_bar(&mut self, ui: &mut egui::Ui, processes: &Vec<(&Pid, &Process)>) -> Option<Kill> {
+        let mut kill = None;
+        let kill_all = |signal: Signal| Kill {
+            pids: processes.iter().map(|(pid, _)| **pid).collect(),
+            signal,
+            description: format!("{} processes", processes.len()),
+            scope: KillScope::All,
+        };
         ui.horizontal(|ui| {
             ui.label("Search:");
             let text_edit = ui.text_edit_singleline(&mut self.search);
-            if self.first {
+            if self.focus_search {
                 text_edit.request_focus();
-                self.first = false;
+                self.focus_search = false;
             }
             ui.checkbox(&mut self.regex, "Regex");
             ui.checkbox(&mut self.label_search, "Label search").on_hover_ui(|ui| {
@@ -222,17 +459,13 @@ impl ProcessListState {
             if ui.button("Kill all").on_hover_ui(|ui| {
                 ui.label("Send KILL to all processes matching the search. If the process of task manager is included, some processes might not be killed.");
             }).clicked() {
-                for (_, process) in processes.iter() {
-                    process.kill();
-                }
+                kill = Some(kill_all(Signal::Kill));
             }
             ui.menu_button("Kill all with", |ui| {
                 ScrollArea::vertical().show(ui, |ui| {
                     for signal in System::SUPPORTED_SIGNALS {
                         if ui.button(format!("Kill with {:?}", signal)).clicked() {
-                            for (_, process) in processes.iter() {
-                                process.kill_with(*signal);
-                            }
+                            kill = Some(kill_all(*signal));
                             ui.close_menu();
                         }
                     }
@@ -243,30 +476,87 @@ impl ProcessListState {
                 });
             })
         });
+        kill
+    }
+
+    fn quick_filters_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Quick filters:");
+            for filter in QuickFilter::ALL {
+                let active = self.quick_filters.contains(&filter);
+                if ui.selectable_label(active, filter.label()).clicked() {
+                    self.toggle_quick_filter(filter);
+                }
+            }
+            if let Some((label, _)) = &self.only {
+                if ui.selectable_label(true, format!("{label} ✖")).clicked() {
+                    self.only = None;
+                }
+            }
+            if !self.quick_filters.is_empty() && ui.button("Clear").clicked() {
+                self.quick_filters.clear();
+            }
+        });
     }
 
-    fn context_menu(ui: &mut egui::Ui, _pid: &sysinfo::Pid, process: &sysinfo::Process) {
+    #[allow(clippy::useless_format)]
+    fn context_menu(
+        ui: &mut egui::Ui,
+        pid: &sysinfo::Pid,
+        process: &sysinfo::Process,
+        system: &System,
+        history: &Mutex<History>,
+        kill: &mut Option<Kill>,
+    ) {
+        let kill_one = |signal: Signal| Kill {
+            pids: vec![*pid],
+            signal,
+            description: format!("{} ({})", process.name(), pid),
+            scope: KillScope::Single,
+        };
         ui.label(format!("{}", process.name()));
         ui.separator();
         if ui.button("Kill").clicked() {
-            process.kill();
+            *kill = Some(kill_one(Signal::Kill));
             ui.close_menu();
         }
         if ui.button("Terminate").clicked() {
-            process.kill_with(Signal::Term);
+            *kill = Some(kill_one(Signal::Term));
+            ui.close_menu();
+        }
+        if ui.button("Kill tree").on_hover_text("Kill this process and all its descendants").clicked() {
+            let pids = descendants(system, *pid);
+            *kill = Some(Kill {
+                description: format!("{} ({}) and its {} descendants", process.name(), pid, pids.len() - 1),
+                pids,
+                signal: Signal::Kill,
+                scope: KillScope::Tree,
+            });
             ui.close_menu();
         }
         ui.menu_button("More options", |ui| {
             ScrollArea::vertical().show(ui, |ui| {
                 for signal in System::SUPPORTED_SIGNALS {
                     if ui.button(format!("Kill with {:?}", signal)).clicked() {
-                        process.kill_with(*signal);
+                        *kill = Some(kill_one(*signal));
                         ui.close_menu();
                     }
                 }
             });
         });
         ui.separator();
+        let mut history = history.lock().unwrap();
+        if history.is_watched(*pid) {
+            if ui.button("Stop plotting").clicked() {
+                history.unwatch(*pid);
+                ui.close_menu();
+            }
+        } else if ui.button("Plot this process").on_hover_text("Show its CPU and memory usage in the graphs view").clicked() {
+            history.watch(*pid, process.name());
+            ui.close_menu();
+        }
+        drop(history);
+        ui.separator();
         if ui.button("Copy name").clicked() {
             let mut clipboard = Clipboard::new().unwrap();
             clipboard.set_text(process.name()).unwrap();
@@ -283,18 +573,39 @@ impl ProcessListState {
         }
     }
 
-    fn table(&mut self, ui: &mut egui::Ui, processes: &Vec<(&Pid, &Process)>, system: &System) {
+    fn table(
+        &mut self,
+        ui: &mut egui::Ui,
+        processes: &Vec<(&Pid, &Process)>,
+        system: &System,
+        history: &Mutex<History>,
+        table_style: TableStyle,
+    ) -> Option<Kill> {
+        let mut kill = None;
+        let selection = self.selection(processes);
         let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+        let header_height = match table_style.density {
+            Density::Comfortable => 20.0,
+            Density::Compact => {
+                ui.spacing_mut().item_spacing.y = 0.0;
+                ui.spacing_mut().button_padding.y = 0.0;
+                text_height
+            }
+        };
 
-        let table = TableBuilder::new(ui)
+        let mut table = TableBuilder::new(ui)
             .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
-            .striped(true)
             .column(Column::auto().at_least(64.0))
             .column(Column::auto().at_least(128.0))
             .column(Column::remainder())
             .min_scrolled_height(0.0);
+        if std::mem::take(&mut self.scroll_to_selected) {
+            if let Some(index) = processes.iter().position(|(pid, _)| Some(**pid) == self.selected) {
+                table = table.scroll_to_row(index, Some(egui::Align::Center));
+            }
+        }
 
-        let table = table.header(20.0, |mut header| {
+        let table = table.header(header_height, |mut header| {
             header.col(|ui| {
                 if ui
                     .add(Label::new(RichText::new("pid").strong()).sense(Sense::click()))
@@ -340,28 +651,46 @@ impl ProcessListState {
         table.body(|body| {
             body.rows(text_height, processes.len(), |row_index, mut row| {
                 let (pid, process) = processes[row_index];
+                let selected = selection.contains(pid);
                 row.col(|ui| {
-                    ui.label(pid.to_string());
+                    if table_style.grid_lines {
+                        grid_lines(ui);
+                    }
+                    let response = ui.selectable_label(selected, pid.to_string());
+                    if response.clicked() {
+                        self.click(ui, *pid);
+                    }
+                    if response.double_clicked() {
+                        self.details = Some(*pid);
+                    }
                 })
                 .1
-                .context_menu(|ui| Self::context_menu(ui, pid, process));
+                .context_menu(|ui| Self::context_menu(ui, pid, process, system, history, &mut kill));
                 row.col(|ui| {
-                    ui.label(
-                        system
-                            .get_user_by_id(process.user_id().unwrap())
-                            .unwrap()
-                            .name(),
-                    );
+                    if table_style.grid_lines {
+                        grid_lines(ui);
+                    }
+                    ui.label(owner_name(system, process));
                 })
                 .1
-                .context_menu(|ui| Self::context_menu(ui, pid, process));
+                .context_menu(|ui| Self::context_menu(ui, pid, process, system, history, &mut kill));
                 row.col(|ui| {
-                    ui.label(process.name());
+                    if table_style.grid_lines {
+                        grid_lines(ui);
+                    }
+                    let response = ui.selectable_label(selected, process.name());
+                    if response.clicked() {
+                        self.click(ui, *pid);
+                    }
+                    if response.double_clicked() {
+                        self.details = Some(*pid);
+                    }
                 })
                 .1
-                .context_menu(|ui| Self::context_menu(ui, pid, process));
+                .context_menu(|ui| Self::context_menu(ui, pid, process, system, history, &mut kill));
             });
         });
+        kill
     }
 
     pub fn process_list_view(
@@ -369,14 +698,171 @@ impl ProcessListState {
         ctx: &egui::Context,
         _frame: &mut eframe::Frame,
         system: Arc<Mutex<System>>,
+        history: Arc<Mutex<History>>,
+        settings: Arc<Mutex<Settings>>,
     ) {
+        let (confirm_kill, table_style, byte_units) = {
+            let settings = settings.lock().unwrap();
+            (settings.confirm_kill, settings.table_style, settings.byte_units)
+        };
         let system = system.lock().unwrap();
         egui::CentralPanel::default().show(ctx, |ui| {
             let processes = self.sorted_processes(&system);
 
-            self.menu_bar(ui, &processes);
+            if self.pending_kill.is_none() {
+                self.navigate(ctx, &processes);
+            }
+
+            let menu_kill = self.menu_bar(ui, &processes);
 
-            self.table(ui, &processes, &system);
+            self.quick_filters_bar(ui);
+
+            let table_kill = self.table(ui, &processes, &system, &history, table_style);
+
+            let kill_selected = std::mem::take(&mut self.kill_selected);
+            let shortcut_kill = match self.selection(&processes).as_slice() {
+                _ if !kill_selected => None,
+                [] => None,
+                [pid] => system.process(*pid).map(|process| Kill {
+                    pids: vec![*pid],
+                    signal: Signal::Kill,
+                    description: format!("{} ({})", process.name(), pid),
+                    scope: KillScope::Single,
+                }),
+                pids => Some(Kill {
+                    pids: pids.to_vec(),
+                    signal: Signal::Kill,
+                    description: format!("{} selected processes", pids.len()),
+                    scope: KillScope::Selection,
+                }),
+            };
+
+            if let Some(kill) = menu_kill.or(table_kill).or(shortcut_kill) {
+                let confirm = match kill.scope {
+                    KillScope::Single => confirm_kill.single,
+                    KillScope::Tree => confirm_kill.tree,
+                    // Several processes at once, like when killing all of them.
+                    KillScope::All | KillScope::Selection => confirm_kill.all,
+                };
+                if confirm {
+                    self.pending_kill = Some(kill);
+                } else {
+                    send(&kill, &system, &history);
+                }
+            }
         });
+
+        if let Some(kill) = &self.pending_kill {
+            let mut open = true;
+            let mut confirmed = false;
+            egui::Window::new("Confirm")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label(format!("Send {:?} to {}?", kill.signal, kill.description));
+                    ui.horizontal(|ui| {
+                        confirmed = ui.button("Send").clicked();
+                        open = !ui.button("Cancel").clicked();
+                    });
+                });
+            if confirmed {
+                send(kill, &system, &history);
+            }
+            if confirmed || !open {
+                self.pending_kill = None;
+            }
+        }
+
+        self.details_window(ctx, &system, byte_units);
+    }
+
+    fn details_window(&mut self, ctx: &egui::Context, system: &System, byte_units: ByteUnits) {
+        let Some(pid) = self.details else {
+            return;
+        };
+        let Some(process) = system.process(pid) else {
+            self.details = None;
+            return;
+        };
+        let (mib, mib_name) = byte_units.unit(2);
+        let rows = [
+            ("Name", process.name().to_string()),
+            ("PID", pid.to_string()),
+            ("Parent", process.parent().map_or("None".to_string(), |parent| parent.to_string())),
+            ("Owner", owner_name(system, process).to_string()),
+            ("Status", process.status().to_string()),
+            ("CPU", format!("{:.1}%", process.cpu_usage())),
+            ("Memory", format!("{:.1} {}", process.memory() as f64 / mib, mib_name)),
+            ("Started", format_time(process.start_time() as i64)),
+            ("Executable", process.exe().display().to_string()),
+            ("Working directory", process.cwd().display().to_string()),
+            ("Command line", process.cmd().join(" ")),
+        ];
+        let mut open = true;
+        egui::Window::new(format!("{} ({})", process.name(), pid))
+            .id(egui::Id::new("process_details"))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                egui::Grid::new("process_details_grid").num_columns(2).show(ui, |ui| {
+                    for (label, value) in rows {
+                        ui.label(label);
+                        ui.add(Label::new(value).wrap(true));
+                        ui.end_row();
+                    }
+                });
+            });
+        if !open {
+            self.details = None;
+        }
+    }
+}
+
+/// Outlines a cell of the process list, with the spacing between the cells
+/// split between its neighbours.
+fn grid_lines(ui: &egui::Ui) {
+    let rect = ui.max_rect().expand2(ui.spacing().item_spacing / 2.0);
+    ui.painter().rect_stroke(rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke);
+}
+
+/// The name of the owner of the process, "Unknown" if the user is not in
+/// the users list.
+fn owner_name<'a>(system: &'a System, process: &Process) -> &'a str {
+    process
+        .user_id()
+        .and_then(|uid| system.get_user_by_id(uid))
+        .map_or("Unknown", |user| user.name())
+}
+
+/// Sends the signal, and logs it as an event on the graphs if any process
+/// received it.
+fn send(kill: &Kill, system: &System, history: &Mutex<History>) {
+    let signaled = kill
+        .pids
+        .iter()
+        .filter(|pid| system.process(**pid).and_then(|process| process.kill_with(kill.signal)) == Some(true))
+        .count();
+    if signaled > 0 {
+        history
+            .lock()
+            .unwrap()
+            .log_event(format!("Sent {:?} to {}", kill.signal, kill.description));
+    }
+}
+
+/// The process and all its descendants, parents first.
+fn descendants(system: &System, pid: Pid) -> Vec<Pid> {
+    let mut pids = vec![pid];
+    let mut index = 0;
+    while let Some(parent) = pids.get(index).copied() {
+        pids.extend(
+            system
+                .processes()
+                .iter()
+                .filter(|(_, process)| process.parent() == Some(parent))
+                .map(|(pid, _)| *pid),
+        );
+        index += 1;
     }
+    pids
 }
@@ -0,0 +1,170 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use sysinfo::{PidExt, ProcessExt, System, SystemExt};
+
+use crate::{
+    metrics::History,
+    series::Series,
+    settings::{Recording, RecordingFormat},
+    snapshot::csv_field,
+};
+
+const CSV_HEADER: &str =
+    "time,cpu,memory_used,memory_total,swap_used,swap_total,network_received,network_transmitted,top_processes\n";
+
+/// A line of the recording. Sizes are in bytes and rates in bytes per
+/// second.
+#[derive(Serialize)]
+struct Sample {
+    /// UNIX timestamp, in seconds.
+    time: f64,
+    /// In percent.
+    cpu: f64,
+    memory_used: u64,
+    memory_total: u64,
+    swap_used: u64,
+    swap_total: u64,
+    network_received: f64,
+    network_transmitted: f64,
+    top_processes: Vec<TopProcess>,
+}
+
+#[derive(Serialize)]
+struct TopProcess {
+    pid: u32,
+    name: String,
+    /// In percent of a core.
+    cpu: f32,
+    memory: u64,
+}
+
+/// Appends the metrics to the file of the settings after every refresh of
+/// the graphs, rotating it once it is too large.
+#[derive(Default)]
+pub struct Recorder {
+    file: Option<(PathBuf, RecordingFormat, File)>,
+    /// Last error, printed once until another one happens.
+    error: Option<String>,
+}
+
+impl Recorder {
+    pub fn record(&mut self, system: &System, history: &History, recording: &Recording) {
+        if !recording.enabled {
+            self.file = None;
+            return;
+        }
+        let result = self.write(&sample(system, history, recording.top_processes), recording);
+        match result {
+            Ok(()) => self.error = None,
+            Err(error) => {
+                self.file = None;
+                if self.error.as_ref() != Some(&error) {
+                    eprintln!("Could not record the metrics to {}: {error}", recording.path);
+                    self.error = Some(error);
+                }
+            }
+        }
+    }
+
+    fn write(&mut self, sample: &Sample, recording: &Recording) -> Result<(), String> {
+        let path = PathBuf::from(&recording.path);
+        if !matches!(&self.file, Some((open, format, _)) if *open == path && *format == recording.format) {
+            self.file = Some((path.clone(), recording.format, open(&path, recording.format)?));
+        }
+        let (_, _, file) = self.file.as_mut().unwrap();
+        let size = file.metadata().map_err(|e| e.to_string())?.len();
+        if size >= recording.max_size_mib * 1024 * 1024 {
+            rotate(&path, recording.kept_files).map_err(|e| e.to_string())?;
+            *file = open(&path, recording.format)?;
+        }
+        let line = match recording.format {
+            RecordingFormat::Csv => csv_line(sample),
+            RecordingFormat::JsonLines => serde_json::to_string(sample).map_err(|e| e.to_string())? + "\n",
+        };
+        file.write_all(line.as_bytes()).map_err(|e| e.to_string())
+    }
+}
+
+fn sample(system: &System, history: &History, top_count: usize) -> Sample {
+    let latest = |series: &Series| series.iter().last().map_or(0.0, |[_, y]| y);
+    let network = |index: usize| history.interfaces.iter().map(|interface| latest(&interface.rates[index])).sum();
+    let mut processes = system.processes().values().collect::<Vec<_>>();
+    processes.sort_by(|a, b| b.cpu_usage().total_cmp(&a.cpu_usage()));
+    Sample {
+        time: history.secs,
+        cpu: latest(&history.cpu),
+        memory_used: system.used_memory(),
+        memory_total: system.total_memory(),
+        swap_used: system.used_swap(),
+        swap_total: system.total_swap(),
+        network_received: network(0),
+        network_transmitted: network(1),
+        top_processes: processes
+            .into_iter()
+            .take(top_count)
+            .map(|process| TopProcess {
+                pid: process.pid().as_u32(),
+                name: process.name().to_string(),
+                cpu: process.cpu_usage(),
+                memory: process.memory(),
+            })
+            .collect(),
+    }
+}
+
+/// The top processes are in a single field, as `name (pid) cpu%`
+/// separated by `;`.
+fn csv_line(sample: &Sample) -> String {
+    let top = sample
+        .top_processes
+        .iter()
+        .map(|process| format!("{} ({}) {:.1}%", process.name, process.pid, process.cpu))
+        .collect::<Vec<_>>()
+        .join("; ");
+    format!(
+        "{:.3},{:.1},{},{},{},{},{:.0},{:.0},{}\n",
+        sample.time,
+        sample.cpu,
+        sample.memory_used,
+        sample.memory_total,
+        sample.swap_used,
+        sample.swap_total,
+        sample.network_received,
+        sample.network_transmitted,
+        csv_field(&top)
+    )
+}
+
+/// Opens the file for appending, writing the CSV header if it is new.
+fn open(path: &Path, format: RecordingFormat) -> Result<File, String> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path).map_err(|e| e.to_string())?;
+    if format == RecordingFormat::Csv && file.metadata().map_err(|e| e.to_string())?.len() == 0 {
+        file.write_all(CSV_HEADER.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    Ok(file)
+}
+
+/// Renames the file to `path.1`, shifting the older ones and dropping the
+/// ones past `kept`.
+fn rotate(path: &Path, kept: usize) -> std::io::Result<()> {
+    let rotated = |index: usize| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    };
+    if kept == 0 {
+        return fs::remove_file(path);
+    }
+    let _ = fs::remove_file(rotated(kept));
+    for index in (1..kept).rev() {
+        if rotated(index).exists() {
+            fs::rename(rotated(index), rotated(index + 1))?;
+        }
+    }
+    fs::rename(path, rotated(1))
+}
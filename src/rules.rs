@@ -0,0 +1,340 @@
+use std::{
+    collections::{HashMap, HashSet},
+    mem::discriminant,
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use egui::{Color32, ComboBox, DragValue, TextEdit};
+use nom::error::VerboseError;
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, Process, ProcessExt, Signal, System, SystemExt, UserExt};
+
+use crate::{
+    metrics::History,
+    parse_labels::{self, Labels},
+    settings::ByteUnits,
+};
+
+/// The resource of a process watched by a rule.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Resource {
+    Cpu,
+    Memory,
+}
+
+impl Resource {
+    const ALL: [Resource; 2] = [Resource::Cpu, Resource::Memory];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Resource::Cpu => "CPU",
+            Resource::Memory => "Memory",
+        }
+    }
+
+    /// Usage of a process, in percent of a core for the CPU and in bytes
+    /// for the memory.
+    fn usage(&self, process: &Process) -> f64 {
+        match self {
+            Resource::Cpu => process.cpu_usage() as f64,
+            Resource::Memory => process.memory() as f64,
+        }
+    }
+
+    fn format(&self, usage: f64, byte_units: ByteUnits) -> String {
+        match self {
+            Resource::Cpu => format!("{usage:.0}%"),
+            Resource::Memory => {
+                let (mib, mib_name) = byte_units.unit(2);
+                format!("{:.0} {mib_name}", usage / mib)
+            }
+        }
+    }
+
+    fn default_threshold(&self) -> f64 {
+        match self {
+            Resource::Cpu => 90.0,
+            Resource::Memory => 4.0 * 1024.0 * 1024.0 * 1024.0,
+        }
+    }
+}
+
+/// What is done to a process triggering a rule. An alert is raised in any
+/// case.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleAction {
+    /// Sends a desktop notification, with the `notifications` feature.
+    Notify,
+    Kill,
+    /// Changes the niceness of the process.
+    Renice(i32),
+    /// Runs a shell command, with the PID and the name of the process in
+    /// `$PID` and `$NAME`.
+    Command(String),
+}
+
+impl RuleAction {
+    fn all() -> [RuleAction; 4] {
+        [RuleAction::Notify, RuleAction::Kill, RuleAction::Renice(10), RuleAction::Command(String::new())]
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            RuleAction::Notify => "Notify",
+            RuleAction::Kill => "Kill",
+            RuleAction::Renice(_) => "Renice",
+            RuleAction::Command(_) => "Run command",
+        }
+    }
+
+    /// What was done, for the alert.
+    fn outcome(&self) -> String {
+        match self {
+            RuleAction::Notify => "notified".to_string(),
+            RuleAction::Kill => "killed".to_string(),
+            RuleAction::Renice(niceness) => format!("reniced to {niceness}"),
+            RuleAction::Command(_) => "command run".to_string(),
+        }
+    }
+}
+
+/// Does something to the processes matching a label search once they stay
+/// past a threshold long enough.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rule {
+    /// Identifies the rule while it is running, whatever its position.
+    pub id: u64,
+    pub enabled: bool,
+    /// Label search selecting the processes, such as `name:chrome`.
+    pub query: String,
+    pub resource: Resource,
+    /// In percent of a core for the CPU, in bytes for the memory.
+    pub threshold: f64,
+    pub duration_secs: u64,
+    pub action: RuleAction,
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self {
+            id: new_id(),
+            enabled: true,
+            query: String::new(),
+            resource: Resource::Memory,
+            threshold: Resource::Memory.default_threshold(),
+            duration_secs: 120,
+            action: RuleAction::Notify,
+        }
+    }
+}
+
+impl Rule {
+    /// The labels of the query, if it is a valid label search. An empty
+    /// query is not, as it would match every process.
+    fn labels(&self) -> Option<Vec<Labels>> {
+        match parse_labels::parse_input::<VerboseError<&str>>(&self.query) {
+            Ok(("", labels)) if !labels.is_empty() => Some(labels),
+            _ => None,
+        }
+    }
+
+    fn description(&self, byte_units: ByteUnits) -> String {
+        format!(
+            "{} {} above {} for {} s",
+            self.query,
+            self.resource.label(),
+            self.resource.format(self.threshold, byte_units),
+            self.duration_secs
+        )
+    }
+}
+
+/// A rule ID unlikely to be used already, including by the rules saved in a
+/// previous run. Rules saved without one are given one when they are read.
+fn new_id() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or_default();
+    nanos.wrapping_add(COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Whether a process matches all the labels, ignoring the case.
+fn matches(labels: &[Labels], process: &Process, system: &System) -> bool {
+    labels.iter().all(|label| match label {
+        Labels::Pid(pid) => process.pid() == Pid::from(*pid),
+        Labels::Owner(owner) => process
+            .user_id()
+            .and_then(|uid| system.get_user_by_id(uid))
+            .map_or(false, |user| user.name().to_lowercase().contains(&owner.to_lowercase())),
+        Labels::Name(name) => process.name().to_lowercase().contains(&name.to_lowercase()),
+    })
+}
+
+/// Applies the rules, on every refresh of the processes.
+#[derive(Default)]
+pub struct Watchdog {
+    /// When each process went past the threshold of each rule, by ID of the
+    /// rule, and whether the rule was applied since.
+    past: HashMap<(u64, Pid), (Instant, bool)>,
+    #[cfg(feature = "notifications")]
+    notifier: crate::notifications::Notifier,
+}
+
+impl Watchdog {
+    pub fn check(&mut self, rules: &[Rule], system: &System, history: &Mutex<History>, byte_units: ByteUnits) {
+        let mut current = HashSet::new();
+        for rule in rules.iter().filter(|rule| rule.enabled) {
+            let Some(labels) = rule.labels() else {
+                continue;
+            };
+            for process in system.processes().values() {
+                if !matches(&labels, process, system) || rule.resource.usage(process) < rule.threshold {
+                    continue;
+                }
+                let key = (rule.id, process.pid());
+                current.insert(key);
+                let (since, applied) = self.past.entry(key).or_insert((Instant::now(), false));
+                if *applied || since.elapsed() < Duration::from_secs(rule.duration_secs) {
+                    continue;
+                }
+                *applied = true;
+                self.apply(rule, process, history, byte_units);
+            }
+        }
+        self.past.retain(|key, _| current.contains(key));
+    }
+
+    fn apply(&mut self, rule: &Rule, process: &Process, history: &Mutex<History>, byte_units: ByteUnits) {
+        let usage = rule.resource.format(rule.resource.usage(process), byte_units);
+        let description = rule.description(byte_units);
+        let result = match &rule.action {
+            #[cfg(feature = "notifications")]
+            RuleAction::Notify => self
+                .notifier
+                .notify(
+                    &format!("{} ({}) is using {usage}", process.name(), process.pid()),
+                    &format!("Rule: {description}"),
+                )
+                .map_err(|e| e.to_string()),
+            #[cfg(not(feature = "notifications"))]
+            RuleAction::Notify => Err("built without notifications".to_string()),
+            RuleAction::Kill => match process.kill_with(Signal::Kill) {
+                Some(true) => Ok(()),
+                _ => Err("could not kill it".to_string()),
+            },
+            // Not waited for, as the processes are locked meanwhile.
+            RuleAction::Renice(niceness) => Command::new("renice")
+                .args(["-n", &niceness.to_string(), "-p", &process.pid().to_string()])
+                .stdout(Stdio::null())
+                .spawn()
+                .map(|mut child| {
+                    thread::spawn(move || {
+                        if let Some(status) = child.wait().ok().filter(|status| !status.success()) {
+                            eprintln!("renice failed: {status}");
+                        }
+                    });
+                })
+                .map_err(|e| e.to_string()),
+            RuleAction::Command(command) => Command::new("sh")
+                .args(["-c", command])
+                .env("PID", process.pid().to_string())
+                .env("NAME", process.name())
+                .spawn()
+                .map(|mut child| {
+                    // Reaps the command once it exits.
+                    thread::spawn(move || child.wait());
+                })
+                .map_err(|e| e.to_string()),
+        };
+        let value = match result {
+            Ok(()) => format!("{usage}, {}", rule.action.outcome()),
+            Err(error) => format!("{usage}, {error}"),
+        };
+        let mut history = history.lock().unwrap();
+        if rule.action == RuleAction::Kill {
+            history.log_event(format!("Rule killed {} ({})", process.name(), process.pid()));
+        }
+        history.raise_alert(
+            format!("Rule: {description}"),
+            value,
+            Some((process.pid(), process.name().to_string())),
+        );
+    }
+}
+
+pub fn rules_ui(rules: &mut Vec<Rule>, ui: &mut egui::Ui, byte_units: ByteUnits) {
+    let mut removed = None;
+    for (index, rule) in rules.iter_mut().enumerate() {
+        ui.push_id(rule.id, |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut rule.enabled, "");
+                ui.add(TextEdit::singleline(&mut rule.query).hint_text("name:chrome").desired_width(120.0));
+                ComboBox::from_id_source("resource")
+                    .selected_text(rule.resource.label())
+                    .show_ui(ui, |ui| {
+                        for resource in Resource::ALL {
+                            if ui.selectable_value(&mut rule.resource, resource, resource.label()).changed() {
+                                rule.threshold = resource.default_threshold();
+                            }
+                        }
+                    });
+                ui.label("above");
+                match rule.resource {
+                    Resource::Cpu => {
+                        ui.add(DragValue::new(&mut rule.threshold).clamp_range(0.0..=10_000.0).suffix("%"));
+                    }
+                    Resource::Memory => {
+                        let (mib, mib_name) = byte_units.unit(2);
+                        let mut threshold = rule.threshold / mib;
+                        let drag = DragValue::new(&mut threshold).speed(16.0).clamp_range(0.0..=f64::MAX).suffix(format!(" {mib_name}"));
+                        if ui.add(drag).changed() {
+                            rule.threshold = threshold * mib;
+                        }
+                    }
+                }
+                ui.label("for");
+                ui.add(DragValue::new(&mut rule.duration_secs).clamp_range(0..=86_400).suffix(" s"));
+                ComboBox::from_id_source("action")
+                    .selected_text(rule.action.label())
+                    .show_ui(ui, |ui| {
+                        for action in RuleAction::all() {
+                            let selected = discriminant(&rule.action) == discriminant(&action);
+                            if ui.selectable_label(selected, action.label()).clicked() && !selected {
+                                rule.action = action;
+                            }
+                        }
+                    });
+                match &mut rule.action {
+                    RuleAction::Renice(niceness) => {
+                        ui.add(DragValue::new(niceness).clamp_range(-20..=19));
+                    }
+                    RuleAction::Command(command) => {
+                        ui.add(TextEdit::singleline(command).hint_text("kill -STOP $PID").desired_width(160.0));
+                    }
+                    _ => {}
+                }
+                if ui.button("Remove").clicked() {
+                    removed = Some(index);
+                }
+            });
+            if rule.labels().is_none() {
+                ui.colored_label(Color32::RED, "The query must be a label search, such as name:chrome");
+            }
+        });
+    }
+    if let Some(index) = removed {
+        rules.remove(index);
+    }
+    if ui.button("Add rule").clicked() {
+        rules.push(Rule::default());
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    fs,
+    sync::{Arc, Mutex},
+};
+
+use egui::{Label, RichText, Sense};
+use egui_extras::{Column, TableBuilder};
+use sysinfo::{ComponentExt, System, SystemExt};
+
+use crate::{
+    process_list::Order,
+    settings::{Level, Settings},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SensorColumn {
+    Label,
+    Temperature,
+    Max,
+    Critical,
+}
+
+impl SensorColumn {
+    const ALL: [SensorColumn; 4] = [
+        SensorColumn::Label,
+        SensorColumn::Temperature,
+        SensorColumn::Max,
+        SensorColumn::Critical,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            SensorColumn::Label => "sensor",
+            SensorColumn::Temperature => "temperature",
+            SensorColumn::Max => "max",
+            SensorColumn::Critical => "critical",
+        }
+    }
+}
+
+/// Speed of a fan, in RPM, from the hwmon sysfs interface.
+pub struct Fan {
+    pub label: String,
+    pub rpm: u64,
+}
+
+pub fn fans() -> Vec<Fan> {
+    let Ok(hwmons) = fs::read_dir("/sys/class/hwmon") else {
+        return vec![];
+    };
+    let mut fans = vec![];
+    for hwmon in hwmons.flatten() {
+        let path = hwmon.path();
+        let chip = fs::read_to_string(path.join("name")).unwrap_or_default();
+        let Ok(files) = fs::read_dir(&path) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let file_name = file.file_name().to_string_lossy().to_string();
+            let Some(fan) = file_name.strip_suffix("_input").filter(|fan| fan.starts_with("fan")) else {
+                continue;
+            };
+            let Some(rpm) = fs::read_to_string(file.path()).ok().and_then(|rpm| rpm.trim().parse().ok()) else {
+                continue;
+            };
+            let label = fs::read_to_string(path.join(format!("{fan}_label")))
+                .map(|label| label.trim().to_string())
+                .unwrap_or_else(|_| format!("{} {fan}", chip.trim()));
+            fans.push(Fan { label, rpm });
+        }
+    }
+    fans.sort_by(|a, b| a.label.cmp(&b.label));
+    fans
+}
+
+pub struct SensorsState {
+    sort: SensorColumn,
+    order: Order,
+}
+
+impl Default for SensorsState {
+    fn default() -> Self {
+        Self {
+            sort: SensorColumn::Label,
+            order: Order::Asc,
+        }
+    }
+}
+
+impl SensorsState {
+    /// Lists the temperature sensors and the fans. Temperatures past the
+    /// warning threshold or close to the critical value of their sensor are
+    /// shown in orange, and in red past the critical ones.
+    pub fn sensors_view(
+        &mut self,
+        ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        system: Arc<Mutex<System>>,
+        settings: Arc<Mutex<Settings>>,
+    ) {
+        let (unit, threshold) = {
+            let settings = settings.lock().unwrap();
+            (settings.temperature_unit, settings.thresholds.temperature)
+        };
+        let system = system.lock().unwrap();
+        let mut components = system.components().iter().collect::<Vec<_>>();
+        components.sort_by(|a, b| match self.sort {
+            SensorColumn::Label => a.label().cmp(b.label()),
+            SensorColumn::Temperature => a.temperature().total_cmp(&b.temperature()),
+            SensorColumn::Max => a.max().total_cmp(&b.max()),
+            SensorColumn::Critical => a.critical().unwrap_or(f32::MIN).total_cmp(&b.critical().unwrap_or(f32::MIN)),
+        });
+        if self.order == Order::Desc {
+            components.reverse();
+        }
+        let fans = fans();
+        let format = |celsius: f32| format!("{:.1} {}", unit.convert(celsius as f64), unit.symbol());
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if components.is_empty() {
+                ui.label("No temperature sensors");
+            }
+            let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+            TableBuilder::new(ui)
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .column(Column::auto().at_least(192.0))
+                .columns(Column::auto().at_least(96.0), 3)
+                .min_scrolled_height(0.0)
+                .header(20.0, |mut header| {
+                    for column in SensorColumn::ALL {
+                        header.col(|ui| {
+                            if ui
+                                .add(Label::new(RichText::new(column.label()).strong()).sense(Sense::click()))
+                                .clicked()
+                            {
+                                if self.sort == column {
+                                    self.order = !self.order;
+                                } else {
+                                    self.order = Order::Asc;
+                                }
+                                self.sort = column;
+                            }
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(text_height, components.len(), |index, mut row| {
+                        let component = components[index];
+                        let own = match component.critical() {
+                            Some(critical) if component.temperature() >= critical => Some(Level::Critical),
+                            Some(critical) if component.temperature() >= critical * 0.9 => Some(Level::Warning),
+                            _ => None,
+                        };
+                        let color = own.max(threshold.level(component.temperature())).map(|level| level.color());
+                        row.col(|ui| {
+                            ui.label(component.label());
+                        });
+                        row.col(|ui| {
+                            let text = RichText::new(format(component.temperature()));
+                            ui.label(match color {
+                                Some(color) => text.color(color).strong(),
+                                None => text,
+                            });
+                        });
+                        row.col(|ui| {
+                            ui.label(format(component.max()));
+                        });
+                        row.col(|ui| {
+                            ui.label(component.critical().map(format).unwrap_or_default());
+                        });
+                    });
+                });
+
+            if !fans.is_empty() {
+                ui.separator();
+                ui.heading("Fans");
+                egui::Grid::new("fans").num_columns(2).show(ui, |ui| {
+                    for fan in &fans {
+                        ui.label(&fan.label);
+                        ui.label(format!("{} RPM", fan.rpm));
+                        ui.end_row();
+                    }
+                });
+            }
+        });
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::VecDeque;
+
+/// Number of seconds kept at full resolution.
+const RECENT_SECS: f64 = 300.0;
+/// Maximum number of downsampled points kept for older samples.
+const ARCHIVE_POINTS: f64 = 3600.0;
+
+/// Bounded history of one metric.
+///
+/// The last few minutes are kept as-is, older samples are averaged into
+/// buckets so that even a 24 hours window holds a fixed number of points.
+#[derive(Default)]
+pub struct Series {
+    recent: VecDeque<[f64;2]>,
+    archive: VecDeque<[f64;2]>,
+    /// Start, sum and count of the points waiting to be archived.
+    bucket: Option<(f64, [f64;2], usize)>,
+}
+
+impl Series {
+    /// Appends a sample and drops everything older than `retention_secs`.
+    pub fn push(&mut self, point: [f64;2], retention_secs: f64) {
+        self.recent.push_back(point);
+        let oldest = point[0] - retention_secs;
+        let oldest_recent = point[0] - RECENT_SECS.min(retention_secs);
+        let resolution = (retention_secs / ARCHIVE_POINTS).max(1.0);
+
+        while let Some(old) = self.recent.front().copied().filter(|[x, _]| *x < oldest_recent) {
+            self.recent.pop_front();
+            if old[0] < oldest {
+                continue;
+            }
+            if let Some((start, [sum_x, sum_y], count)) = self.bucket {
+                if old[0] - start >= resolution {
+                    self.archive.push_back([sum_x / count as f64, sum_y / count as f64]);
+                    self.bucket = None;
+                }
+            }
+            let (_, [sum_x, sum_y], count) = self.bucket.get_or_insert((old[0], [0.0, 0.0], 0));
+            *sum_x += old[0];
+            *sum_y += old[1];
+            *count += 1;
+        }
+        while self.archive.front().filter(|[x, _]| *x < oldest).is_some() {
+            self.archive.pop_front();
+        }
+    }
+
+    /// All the points, oldest first.
+    pub fn points(&self) -> Vec<[f64;2]> {
+        self.iter().collect()
+    }
+
+    /// All the points, oldest first, including the average of the bucket
+    /// not archived yet so that there is no gap before the recent samples.
+    pub fn iter(&self) -> impl Iterator<Item = [f64;2]> + '_ {
+        let pending = self
+            .bucket
+            .map(|(_, [sum_x, sum_y], count)| [sum_x / count as f64, sum_y / count as f64]);
+        self.archive.iter().copied().chain(pending).chain(self.recent.iter().copied())
+    }
+}
+
+/// Adds up series. They are resampled at the times of the longest one, as
+/// the older samples of series started at different times are averaged
+/// into buckets at different times.
+pub fn sum_points<'a>(series: impl IntoIterator<Item = &'a Series>) -> Vec<[f64;2]> {
+    let series = series.into_iter().map(Series::points).collect::<Vec<_>>();
+    let Some(longest) = series.iter().max_by_key(|points| points.len()) else {
+        return vec![];
+    };
+    longest
+        .iter()
+        .map(|[x, _]| [*x, series.iter().map(|points| value_at(points, *x)).sum()])
+        .collect()
+}
+
+/// The value of the points at `x`, interpolated between the two around it,
+/// and 0 outside of them.
+fn value_at(points: &[[f64;2]], x: f64) -> f64 {
+    let after = points.partition_point(|[point_x, _]| *point_x < x);
+    match (after.checked_sub(1).map(|before| points[before]), points.get(after)) {
+        (_, Some([next_x, next_y])) if *next_x == x => *next_y,
+        (Some([previous_x, previous_y]), Some([next_x, next_y])) => {
+            previous_y + (next_y - previous_y) * (x - previous_x) / (next_x - previous_x)
+        }
+        _ => 0.0,
+    }
+}
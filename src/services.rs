@@ -0,0 +1,349 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use egui_extras::{Column, TableBuilder};
+use zbus::{
+    blocking::{Connection, Proxy},
+    zvariant::OwnedObjectPath,
+    MethodFlags,
+};
+
+use crate::settings::Settings;
+
+/// A systemd service, as listed by `ListUnits`.
+pub struct Unit {
+    pub name: String,
+    pub description: String,
+    pub load_state: String,
+    pub active_state: String,
+    pub sub_state: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum UnitAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl UnitAction {
+    const ALL: [UnitAction; 3] = [UnitAction::Start, UnitAction::Stop, UnitAction::Restart];
+
+    fn label(&self) -> &'static str {
+        match self {
+            UnitAction::Start => "Start",
+            UnitAction::Stop => "Stop",
+            UnitAction::Restart => "Restart",
+        }
+    }
+
+    /// Method of `org.freedesktop.systemd1.Manager` doing this action.
+    fn method(&self) -> &'static str {
+        match self {
+            UnitAction::Start => "StartUnit",
+            UnitAction::Stop => "StopUnit",
+            UnitAction::Restart => "RestartUnit",
+        }
+    }
+}
+
+/// The connections to the system and session buses, opened on first use and
+/// shared with the threads making the calls.
+#[derive(Clone, Default)]
+pub struct Buses {
+    system: Arc<Mutex<Option<Connection>>>,
+    session: Arc<Mutex<Option<Connection>>>,
+}
+
+impl Buses {
+    /// Makes calls on the session bus if `user`, on the system bus
+    /// otherwise. The connection is opened again for the next calls if they
+    /// fail, in case it was lost.
+    pub fn with<T>(&self, user: bool, calls: impl FnOnce(&Connection) -> Result<T, String>) -> Result<T, String> {
+        let slot = if user { &self.session } else { &self.system };
+        let connection = {
+            let mut slot = slot.lock().unwrap();
+            match &*slot {
+                Some(connection) => connection.clone(),
+                None => {
+                    let connection = if user { Connection::session() } else { Connection::system() };
+                    let connection = connection.map_err(|e| e.to_string())?;
+                    *slot = Some(connection.clone());
+                    connection
+                }
+            }
+        };
+        let result = calls(&connection);
+        if result.is_err() {
+            *slot.lock().unwrap() = None;
+        }
+        result
+    }
+}
+
+/// The manager of the system services, or of the services of the current
+/// user, depending on the bus.
+fn manager(connection: &Connection) -> zbus::Result<Proxy<'static>> {
+    Proxy::new(
+        connection,
+        "org.freedesktop.systemd1",
+        "/org/freedesktop/systemd1",
+        "org.freedesktop.systemd1.Manager",
+    )
+}
+
+type ListedUnit = (String, String, String, String, String, String, OwnedObjectPath, u32, String, OwnedObjectPath);
+
+pub fn units(connection: &Connection) -> Result<Vec<Unit>, String> {
+    let units: Vec<ListedUnit> = manager(connection)
+        .and_then(|manager| manager.call("ListUnits", &()))
+        .map_err(|e| e.to_string())?;
+    let mut units = units
+        .into_iter()
+        .filter(|unit| unit.0.ends_with(".service"))
+        .map(|(name, description, load_state, active_state, sub_state, ..)| Unit {
+            name,
+            description,
+            load_state,
+            active_state,
+            sub_state,
+        })
+        .collect::<Vec<_>>();
+    units.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(units)
+}
+
+/// Starts, stops or restarts a unit. For system units, polkit asks the user
+/// to authenticate if needed, so this blocks until they did.
+pub fn run(connection: &Connection, unit: &str, action: UnitAction) -> Result<(), String> {
+    manager(connection)
+        .and_then(|manager| {
+            manager.call_with_flags::<_, _, OwnedObjectPath>(
+                action.method(),
+                MethodFlags::AllowInteractiveAuth.into(),
+                &(unit, "replace"),
+            )
+        })
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// The services of the current user that can be enabled, with whether they
+/// are, from `ListUnitFiles` on the session bus.
+pub fn user_unit_files(connection: &Connection) -> Result<Vec<(String, bool)>, String> {
+    let files: Vec<(String, String)> = manager(connection)
+        .and_then(|manager| manager.call("ListUnitFiles", &()))
+        .map_err(|e| e.to_string())?;
+    let mut files = files
+        .into_iter()
+        .filter(|(_, state)| state == "enabled" || state == "disabled")
+        .filter_map(|(path, state)| {
+            let name = path.rsplit('/').next()?.to_string();
+            name.ends_with(".service").then_some((name, state == "enabled"))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+/// Enables or disables a service of the current user, like `systemctl
+/// --user enable`, on the session bus.
+pub fn set_user_unit_enabled(connection: &Connection, unit: &str, enabled: bool) -> Result<(), String> {
+    let manager = manager(connection).map_err(|e| e.to_string())?;
+    let result = if enabled {
+        manager
+            .call::<_, _, (bool, Vec<(String, String, String)>)>("EnableUnitFiles", &(&[unit], false, false))
+            .map(|_| ())
+    } else {
+        manager
+            .call::<_, _, Vec<(String, String, String)>>("DisableUnitFiles", &(&[unit], false))
+            .map(|_| ())
+    };
+    result
+        .and_then(|()| manager.call::<_, _, ()>("Reload", &()))
+        .map_err(|e| e.to_string())
+}
+
+/// A lock preventing the machine from sleeping or shutting down, from
+/// logind.
+pub struct Inhibitor {
+    /// What is inhibited, such as `sleep:idle`.
+    pub what: String,
+    pub who: String,
+    pub why: String,
+    /// `block`, or `delay` for a lock that only delays it.
+    pub mode: String,
+    pub pid: u32,
+}
+
+/// The inhibitors, asked to logind on the system bus.
+pub fn inhibitors(connection: &Connection) -> Result<Vec<Inhibitor>, String> {
+    let inhibitors: Vec<(String, String, String, String, u32, u32)> = Proxy::new(
+        connection,
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1",
+        "org.freedesktop.login1.Manager",
+    )
+    .and_then(|manager| manager.call("ListInhibitors", &()))
+    .map_err(|e| e.to_string())?;
+    Ok(inhibitors
+        .into_iter()
+        .map(|(what, who, why, mode, _uid, pid)| Inhibitor {
+            what,
+            who,
+            why,
+            mode,
+            pid,
+        })
+        .collect())
+}
+
+/// The services listed by a thread, with whether they are the ones of the
+/// user.
+type Listing = (bool, Result<Vec<Unit>, String>);
+
+#[derive(Default)]
+pub struct ServicesState {
+    /// Whether the services of the current user are shown, rather than the
+    /// system ones.
+    user: bool,
+    filter: String,
+    units: Vec<Unit>,
+    error: Option<String>,
+    refreshed: Option<Instant>,
+    /// Whether a thread is listing the services, as D-Bus calls block.
+    listing: bool,
+    /// Set by the thread listing them.
+    listed: Arc<Mutex<Option<Listing>>>,
+    buses: Buses,
+    /// Outcome of the last action, set by the thread running it.
+    status: Arc<Mutex<Option<String>>>,
+}
+
+impl ServicesState {
+    pub fn services_view(
+        &mut self,
+        ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        settings: Arc<Mutex<Settings>>,
+    ) {
+        let interval = Duration::from_millis(settings.lock().unwrap().refresh_intervals.processes_ms);
+        if let Some((user, listed)) = self.listed.lock().unwrap().take() {
+            self.listing = false;
+            // Left out if the other services were chosen meanwhile.
+            if user == self.user {
+                match listed {
+                    Ok(units) => {
+                        self.units = units;
+                        self.error = None;
+                    }
+                    Err(error) => self.error = Some(error),
+                }
+            }
+        }
+        if !self.listing && self.refreshed.map_or(true, |time| time.elapsed() >= interval) {
+            self.listing = true;
+            self.refreshed = Some(Instant::now());
+            let user = self.user;
+            let listed = self.listed.clone();
+            let buses = self.buses.clone();
+            let ctx = ctx.clone();
+            thread::spawn(move || {
+                *listed.lock().unwrap() = Some((user, buses.with(user, units)));
+                ctx.request_repaint();
+            });
+        }
+        ctx.request_repaint_after(interval);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let system = ui.selectable_value(&mut self.user, false, "System");
+                let user = ui.selectable_value(&mut self.user, true, "User");
+                if system.changed() || user.changed() {
+                    self.units.clear();
+                    self.refreshed = None;
+                }
+                ui.separator();
+                ui.label("Search:");
+                ui.text_edit_singleline(&mut self.filter);
+            });
+            if let Some(error) = &self.error {
+                ui.label(format!("Could not list the services: {error}"));
+            }
+            if let Some(status) = &*self.status.lock().unwrap() {
+                ui.label(status);
+            }
+
+            let filter = self.filter.to_lowercase();
+            let units = self
+                .units
+                .iter()
+                .filter(|unit| {
+                    unit.name.to_lowercase().contains(&filter) || unit.description.to_lowercase().contains(&filter)
+                })
+                .collect::<Vec<_>>();
+            let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+            let mut action = None;
+            TableBuilder::new(ui)
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .column(Column::auto().at_least(192.0))
+                .column(Column::auto().at_least(64.0))
+                .column(Column::auto().at_least(128.0))
+                .column(Column::auto())
+                .column(Column::remainder())
+                .min_scrolled_height(0.0)
+                .header(20.0, |mut header| {
+                    for title in ["unit", "load", "state", "actions", "description"] {
+                        header.col(|ui| {
+                            ui.strong(title);
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(text_height, units.len(), |index, mut row| {
+                        let unit = units[index];
+                        row.col(|ui| {
+                            ui.label(&unit.name);
+                        });
+                        row.col(|ui| {
+                            ui.label(&unit.load_state);
+                        });
+                        row.col(|ui| {
+                            ui.label(format!("{} ({})", unit.active_state, unit.sub_state));
+                        });
+                        row.col(|ui| {
+                            for unit_action in UnitAction::ALL {
+                                if ui.small_button(unit_action.label()).clicked() {
+                                    action = Some((unit.name.clone(), unit_action));
+                                }
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(&unit.description);
+                        });
+                    });
+                });
+
+            // Authenticating can take a while, so the action is run apart
+            // from the UI.
+            if let Some((unit, action)) = action {
+                let user = self.user;
+                let status = self.status.clone();
+                let buses = self.buses.clone();
+                let ctx = ctx.clone();
+                *status.lock().unwrap() = Some(format!("{} {unit}...", action.label()));
+                thread::spawn(move || {
+                    let message = match buses.with(user, |connection| run(connection, &unit, action)) {
+                        Ok(()) => format!("{} {unit}: done", action.label()),
+                        Err(error) => format!("{} {unit}: {error}", action.label()),
+                    };
+                    *status.lock().unwrap() = Some(message);
+                    ctx.request_repaint();
+                });
+            }
+        });
+    }
+}
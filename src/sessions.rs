@@ -0,0 +1,125 @@
+use std::{
+    fs,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use sysinfo::{ProcessExt, System, SystemExt, UserExt};
+
+use crate::{format::format_time, settings::Settings};
+
+/// A login session, from `/var/run/utmp`.
+pub struct Session {
+    pub user: String,
+    /// Terminal of the session, such as `tty1` or `pts/0`.
+    pub line: String,
+    /// Remote host or X display, if any.
+    pub host: String,
+    /// UNIX timestamp of the login.
+    pub login: i64,
+}
+
+/// Layout of `struct utmp` in glibc on Linux.
+const UTMP_SIZE: usize = 384;
+const USER_PROCESS: i16 = 7;
+
+fn sessions() -> Vec<Session> {
+    let Ok(utmp) = fs::read("/var/run/utmp") else {
+        return vec![];
+    };
+    let text = |bytes: &[u8]| {
+        let end = bytes.iter().position(|byte| *byte == 0).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end]).to_string()
+    };
+    utmp.chunks_exact(UTMP_SIZE)
+        .filter(|record| i16::from_ne_bytes([record[0], record[1]]) == USER_PROCESS)
+        .map(|record| Session {
+            line: text(&record[8..40]),
+            user: text(&record[44..76]),
+            host: text(&record[76..332]),
+            login: i32::from_ne_bytes(record[340..344].try_into().unwrap()) as i64,
+        })
+        .collect()
+}
+
+#[derive(Default)]
+pub struct SessionsState {
+    sessions: Vec<Session>,
+    /// `/var/run/utmp` is only read again at the refresh interval of the
+    /// processes.
+    refreshed: Option<Instant>,
+}
+
+impl SessionsState {
+    /// Shows the logged in users, with their sessions and the resources used
+    /// by their processes. Returns the user whose processes to show in the
+    /// process list, if one was clicked.
+    pub fn sessions_view(
+        &mut self,
+        ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        system: Arc<Mutex<System>>,
+        settings: Arc<Mutex<Settings>>,
+    ) -> Option<String> {
+        let (interval, byte_units) = {
+            let settings = settings.lock().unwrap();
+            (Duration::from_millis(settings.refresh_intervals.processes_ms), settings.byte_units)
+        };
+        if self.refreshed.map_or(true, |time| time.elapsed() >= interval) {
+            self.sessions = sessions();
+            self.refreshed = Some(Instant::now());
+        }
+        ctx.request_repaint_after(interval);
+
+        let (mib, mib_name) = byte_units.unit(2);
+        let system = system.lock().unwrap();
+        let sessions = &self.sessions;
+        let mut users = sessions.iter().map(|session| session.user.clone()).collect::<Vec<_>>();
+        users.sort();
+        users.dedup();
+        let mut jump = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if users.is_empty() {
+                ui.label("No logged in users");
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for user in users {
+                    let processes = system
+                        .processes()
+                        .values()
+                        .filter(|process| {
+                            process
+                                .user_id()
+                                .and_then(|uid| system.get_user_by_id(uid))
+                                .map_or(false, |owner| owner.name() == user)
+                        })
+                        .collect::<Vec<_>>();
+                    let cpu = processes.iter().map(|process| process.cpu_usage()).sum::<f32>();
+                    let memory = processes.iter().map(|process| process.memory()).sum::<u64>() as f64;
+                    ui.horizontal(|ui| {
+                        ui.heading(&user);
+                        if ui
+                            .link(format!("{} processes", processes.len()))
+                            .on_hover_text("Show in the process list")
+                            .clicked()
+                        {
+                            jump = Some(user.clone());
+                        }
+                        ui.label(format!("CPU {cpu:.1}%"));
+                        ui.label(format!("Memory {:.1} {}", memory / mib, mib_name));
+                    });
+                    egui::Grid::new(("sessions", &user)).num_columns(3).show(ui, |ui| {
+                        for session in sessions.iter().filter(|session| session.user == user) {
+                            ui.label(&session.line);
+                            ui.label(&session.host);
+                            ui.label(format_time(session.login));
+                            ui.end_row();
+                        }
+                    });
+                    ui.separator();
+                }
+            });
+        });
+        jump
+    }
+}
@@ -1,20 +1,721 @@
-use egui::DragValue;
+use std::{
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use egui::{Color32, ComboBox, DragValue};
 use serde::{Serialize, Deserialize};
 
+use crate::{
+    autostart,
+    graphs::GraphKind,
+    keybindings::Keybindings,
+    process_list::{Columns, Order},
+    rules::{self, Rule},
+};
+
+/// Size of the body text in the default egui style.
+const DEFAULT_FONT_SIZE: f32 = 14.0;
+/// Allowed refresh intervals, in milliseconds.
+pub const REFRESH_INTERVAL_MS: RangeInclusive<u64> = 100..=60_000;
+
 #[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
-    pub update_interval_ms: usize,
+    pub refresh_intervals: RefreshIntervals,
+    /// The single refresh interval of older versions, moved to
+    /// `refresh_intervals` by `migrate`.
+    #[serde(skip_serializing)]
+    update_interval_ms: Option<u64>,
+    pub graph_retention: GraphRetention,
+    pub graphs_layout: GraphsLayout,
+    pub cpu_normalization: CpuNormalization,
+    pub network_graph: NetworkGraph,
+    pub disk_graph: DiskGraph,
+    pub theme: Theme,
+    /// Color of the selection and of the links, the one of the theme if
+    /// unset.
+    pub accent_color: Option<egui::Color32>,
+    pub table_style: TableStyle,
+    /// Zoom factor applied on top of the scale of the screen.
+    pub ui_scale: f32,
+    /// Size of the body text, in points. Other text styles are scaled
+    /// accordingly.
+    pub font_size: f32,
+    pub byte_units: ByteUnits,
+    pub temperature_unit: TemperatureUnit,
+    pub default_view: DefaultView,
+    pub confirm_kill: ConfirmKill,
+    /// Initial sort of the process list.
+    pub sort_column: Columns,
+    pub sort_order: Order,
+    pub keybindings: Keybindings,
+    pub window: WindowBehavior,
+    pub thresholds: Thresholds,
+    pub notifications: Notifications,
+    pub rules: Vec<Rule>,
+    pub recording: Recording,
+    #[serde(skip)]
+    view: SettingsViewState,
+}
+
+/// State of the settings view, which is not saved.
+struct SettingsViewState {
+    category: Category,
+    filter: String,
+    /// File the settings are exported to and imported from.
+    transfer_path: String,
+    transfer_status: Option<String>,
+    autostart_error: Option<String>,
+}
+
+impl Default for SettingsViewState {
+    fn default() -> Self {
+        Self {
+            category: Category::General,
+            filter: String::new(),
+            transfer_path: "task-manager-settings.json".to_string(),
+            transfer_status: None,
+            autostart_error: None,
+        }
+    }
+}
+
+/// The tabs of the settings view.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Category {
+    General,
+    Appearance,
+    ProcessList,
+    Graphs,
+    Alerts,
+}
+
+impl Category {
+    const ALL: [Category; 5] = [
+        Category::General,
+        Category::Appearance,
+        Category::ProcessList,
+        Category::Graphs,
+        Category::Alerts,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Category::General => "General",
+            Category::Appearance => "Appearance",
+            Category::ProcessList => "Process list",
+            Category::Graphs => "Graphs",
+            Category::Alerts => "Alerts",
+        }
+    }
 }
 
+/// A row of the settings view: its tab, its label, and its widgets.
+type Row = (Category, &'static str, fn(&mut Settings, &mut egui::Ui));
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
-            update_interval_ms: 1000,
+            refresh_intervals: RefreshIntervals::default(),
+            update_interval_ms: None,
+            graph_retention: GraphRetention::FiveMinutes,
+            graphs_layout: GraphsLayout::default(),
+            cpu_normalization: CpuNormalization::Machine,
+            network_graph: NetworkGraph::default(),
+            disk_graph: DiskGraph::default(),
+            theme: Theme::System,
+            accent_color: None,
+            table_style: TableStyle::default(),
+            ui_scale: 1.0,
+            font_size: DEFAULT_FONT_SIZE,
+            byte_units: ByteUnits::Binary,
+            temperature_unit: TemperatureUnit::Celsius,
+            default_view: DefaultView::Processes,
+            confirm_kill: ConfirmKill::default(),
+            sort_column: Columns::Pid,
+            sort_order: Order::Asc,
+            keybindings: Keybindings::default(),
+            window: WindowBehavior::default(),
+            thresholds: Thresholds::default(),
+            notifications: Notifications::default(),
+            recording: Recording::default(),
+            rules: Vec::new(),
+            view: SettingsViewState::default(),
+        }
+    }
+}
+
+/// How often each kind of data is refreshed, in milliseconds.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RefreshIntervals {
+    pub processes_ms: u64,
+    /// Also the interval between two samples of the graphs.
+    pub graphs_ms: u64,
+    /// Temperature sensors, which can be slow to read.
+    pub sensors_ms: u64,
+    /// Whether processes are refreshed while the process list is not shown.
+    /// The process graphs need it.
+    pub processes_in_background: bool,
+}
+
+impl Default for RefreshIntervals {
+    fn default() -> Self {
+        Self {
+            processes_ms: 1000,
+            graphs_ms: 1000,
+            sensors_ms: 1000,
+            processes_in_background: true,
+        }
+    }
+}
+
+/// How long the graphs history is kept.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GraphRetention {
+    FiveMinutes,
+    OneHour,
+    OneDay,
+}
+
+impl GraphRetention {
+    const ALL: [GraphRetention; 3] = [
+        GraphRetention::FiveMinutes,
+        GraphRetention::OneHour,
+        GraphRetention::OneDay,
+    ];
+
+    pub fn secs(&self) -> f64 {
+        match self {
+            GraphRetention::FiveMinutes => 5.0 * 60.0,
+            GraphRetention::OneHour => 60.0 * 60.0,
+            GraphRetention::OneDay => 24.0 * 60.0 * 60.0,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            GraphRetention::FiveMinutes => "5 minutes",
+            GraphRetention::OneHour => "1 hour",
+            GraphRetention::OneDay => "24 hours",
+        }
+    }
+}
+
+/// How the window behaves, for the app to be used as an always available
+/// monitor.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowBehavior {
+    pub start_minimized: bool,
+    /// Closing the window minimizes it, and File > Quit quits.
+    pub minimize_on_close: bool,
+    pub launch_on_login: bool,
+}
+
+/// How bad a metric past its thresholds is.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Warning,
+    Critical,
+}
+
+impl Level {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Level::Warning => "warning",
+            Level::Critical => "critical",
+        }
+    }
+
+    /// Color of the values at this level, in every view.
+    pub fn color(&self) -> Color32 {
+        match self {
+            Level::Warning => Color32::from_rgb(255, 165, 0),
+            Level::Critical => Color32::RED,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Threshold {
+    pub warning: f32,
+    pub critical: f32,
+}
+
+impl Threshold {
+    pub fn level(&self, value: f32) -> Option<Level> {
+        if value >= self.critical {
+            Some(Level::Critical)
+        } else if value >= self.warning {
+            Some(Level::Warning)
+        } else {
+            None
+        }
+    }
+}
+
+/// Thresholds of the metrics, in percent of the usage, and in degrees
+/// Celsius for the hottest temperature sensor. They color the values, raise
+/// alerts, and the critical ones trigger the notifications.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thresholds {
+    pub cpu: Threshold,
+    pub memory: Threshold,
+    pub swap: Threshold,
+    /// Space used on the fullest disk.
+    pub disk: Threshold,
+    pub temperature: Threshold,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            cpu: Threshold { warning: 75.0, critical: 90.0 },
+            memory: Threshold { warning: 80.0, critical: 90.0 },
+            swap: Threshold { warning: 50.0, critical: 80.0 },
+            disk: Threshold { warning: 85.0, critical: 95.0 },
+            temperature: Threshold { warning: 80.0, critical: 90.0 },
+        }
+    }
+}
+
+/// Desktop notifications sent when a metric stays past its critical
+/// threshold.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Notifications {
+    pub enabled: bool,
+    /// How long a metric has to stay past its threshold, in seconds.
+    pub duration_secs: u64,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            duration_secs: 30,
+        }
+    }
+}
+
+/// How the recorded metrics are written.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecordingFormat {
+    Csv,
+    JsonLines,
+}
+
+impl RecordingFormat {
+    const ALL: [RecordingFormat; 2] = [RecordingFormat::Csv, RecordingFormat::JsonLines];
+
+    fn label(&self) -> &'static str {
+        match self {
+            RecordingFormat::Csv => "CSV",
+            RecordingFormat::JsonLines => "JSON lines",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            RecordingFormat::Csv => "csv",
+            RecordingFormat::JsonLines => "jsonl",
+        }
+    }
+}
+
+/// Metrics appended to a file at every refresh of the graphs, to look into
+/// them later.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Recording {
+    pub enabled: bool,
+    pub path: String,
+    pub format: RecordingFormat,
+    /// Number of most CPU hungry processes written with each sample.
+    pub top_processes: usize,
+    /// Size past which the file is rotated, in MiB.
+    pub max_size_mib: u64,
+    /// Number of rotated files kept, as `path.1` being the latest.
+    pub kept_files: usize,
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "task-manager-metrics.csv".to_string(),
+            format: RecordingFormat::Csv,
+            top_processes: 0,
+            max_size_mib: 10,
+            kept_files: 5,
+        }
+    }
+}
+
+/// How the tables and grids are drawn.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TableStyle {
+    /// Alternate the background of the rows.
+    pub striped: bool,
+    /// Draw lines between the cells of the process list.
+    pub grid_lines: bool,
+    pub density: Density,
+}
+
+/// How tight the rows of the process list are.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Density {
+    #[default]
+    Comfortable,
+    /// No spacing between the rows, to fit more processes on screen.
+    Compact,
+}
+
+impl Density {
+    const ALL: [Density; 2] = [Density::Comfortable, Density::Compact];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Density::Comfortable => "Comfortable",
+            Density::Compact => "Compact",
+        }
+    }
+}
+
+impl Default for TableStyle {
+    fn default() -> Self {
+        Self {
+            striped: true,
+            grid_lines: false,
+            density: Density::Comfortable,
+        }
+    }
+}
+
+/// Which kills from the process list ask for a confirmation first.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmKill {
+    pub single: bool,
+    /// A process and its descendants.
+    pub tree: bool,
+    /// "Kill all", for all the processes matching the search.
+    pub all: bool,
+}
+
+impl Default for ConfirmKill {
+    fn default() -> Self {
+        Self {
+            single: false,
+            tree: true,
+            all: true,
+        }
+    }
+}
+
+/// The view the app opens into.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DefaultView {
+    Processes,
+    Performance,
+    Graphs,
+}
+
+impl DefaultView {
+    const ALL: [DefaultView; 3] = [DefaultView::Processes, DefaultView::Performance, DefaultView::Graphs];
+
+    fn label(&self) -> &'static str {
+        match self {
+            DefaultView::Processes => "Processes",
+            DefaultView::Performance => "Performance",
+            DefaultView::Graphs => "Graphs",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    /// Follow the theme of the desktop, dark if it is unknown.
+    System,
+    Dark,
+    Light,
+}
+
+impl Theme {
+    const ALL: [Theme; 3] = [Theme::System, Theme::Dark, Theme::Light];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Theme::System => "Follow system",
+            Theme::Dark => "Dark",
+            Theme::Light => "Light",
+        }
+    }
+
+    pub fn visuals(&self, system_theme: Option<eframe::Theme>) -> egui::Visuals {
+        match (self, system_theme) {
+            (Theme::Light, _) | (Theme::System, Some(eframe::Theme::Light)) => egui::Visuals::light(),
+            _ => egui::Visuals::dark(),
+        }
+    }
+}
+
+/// How sizes in bytes are displayed.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ByteUnits {
+    /// Powers of 1024: KiB, MiB, GiB...
+    Binary,
+    /// Powers of 1000: kB, MB, GB...
+    Decimal,
+    Bytes,
+}
+
+impl ByteUnits {
+    const ALL: [ByteUnits; 3] = [ByteUnits::Binary, ByteUnits::Decimal, ByteUnits::Bytes];
+
+    fn label(&self) -> &'static str {
+        match self {
+            ByteUnits::Binary => "Binary (KiB, MiB, GiB)",
+            ByteUnits::Decimal => "Decimal (kB, MB, GB)",
+            ByteUnits::Bytes => "Bytes",
+        }
+    }
+
+    /// Size and name of the unit for the given power of 1024 or 1000, such
+    /// as GiB for 3.
+    pub fn unit(&self, power: usize) -> (f64, &'static str) {
+        let power = power.min(4);
+        match self {
+            ByteUnits::Binary => (1024f64.powi(power as i32), ["B", "KiB", "MiB", "GiB", "TiB"][power]),
+            ByteUnits::Decimal => (1000f64.powi(power as i32), ["B", "kB", "MB", "GB", "TB"][power]),
+            ByteUnits::Bytes => (1.0, "B"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    const ALL: [TemperatureUnit; 2] = [TemperatureUnit::Celsius, TemperatureUnit::Fahrenheit];
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+        }
+    }
+
+    /// Converts a temperature in degrees Celsius, as reported by the sensors.
+    pub fn convert(&self, celsius: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    /// Converts a temperature in this unit back to degrees Celsius.
+    pub fn celsius(&self, value: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => value,
+            TemperatureUnit::Fahrenheit => (value - 32.0) * 5.0 / 9.0,
+        }
+    }
+
+    /// Converts a threshold in degrees Celsius.
+    pub fn convert_threshold(&self, threshold: Threshold) -> Threshold {
+        Threshold {
+            warning: self.convert(threshold.warning as f64) as f32,
+            critical: self.convert(threshold.critical as f64) as f32,
+        }
+    }
+}
+
+/// What 100% of CPU usage stands for.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CpuNormalization {
+    /// The whole machine is busy.
+    Machine,
+    /// One core is busy, so a machine with 8 cores goes up to 800%.
+    PerCore,
+}
+
+impl CpuNormalization {
+    pub const ALL: [CpuNormalization; 2] = [
+        CpuNormalization::Machine,
+        CpuNormalization::PerCore,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CpuNormalization::Machine => "% of the whole machine",
+            CpuNormalization::PerCore => "% of one core",
+        }
+    }
+
+    /// Converts a usage where 100% is the whole machine.
+    pub fn machine_usage(&self, usage: f64, cores: usize) -> f64 {
+        match self {
+            CpuNormalization::Machine => usage,
+            CpuNormalization::PerCore => usage * cores.max(1) as f64,
+        }
+    }
+
+    /// Converts a usage where 100% is one core, as reported for processes.
+    pub fn core_usage(&self, usage: f64, cores: usize) -> f64 {
+        match self {
+            CpuNormalization::Machine => usage / cores.max(1) as f64,
+            CpuNormalization::PerCore => usage,
+        }
+    }
+}
+
+/// Which network interfaces the network graph includes.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkGraph {
+    /// Show one series per interface instead of their sum.
+    pub per_interface: bool,
+    pub excluded: Vec<String>,
+}
+
+impl Default for NetworkGraph {
+    fn default() -> Self {
+        Self {
+            per_interface: false,
+            excluded: vec!["lo".to_string()],
+        }
+    }
+}
+
+/// Which block devices the disk I/O graph includes.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiskGraph {
+    pub excluded: Vec<String>,
+}
+
+/// Which graphs are shown in the graphs view, in which order, and in how
+/// many columns.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphsLayout {
+    pub columns: usize,
+    pub graphs: Vec<GraphKind>,
+}
+
+impl Default for GraphsLayout {
+    fn default() -> Self {
+        Self {
+            columns: 1,
+            graphs: GraphKind::ALL.to_vec(),
+        }
+    }
+}
+
+impl GraphsLayout {
+    pub fn layout_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Columns");
+            ui.add(DragValue::new(&mut self.columns).clamp_range(1..=4));
+            if ui.button("1x1").clicked() {
+                self.columns = 1;
+            }
+            if ui.button("2x2").clicked() {
+                self.columns = 2;
+            }
+        });
+        ui.separator();
+        let shown = self.graphs.clone();
+        for (index, kind) in shown.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let mut visible = true;
+                if ui.checkbox(&mut visible, kind.title()).changed() {
+                    self.graphs.retain(|graph| graph != kind);
+                }
+                if ui.add_enabled(index > 0, egui::Button::new("⬆")).clicked() {
+                    self.graphs.swap(index, index - 1);
+                }
+                if ui.add_enabled(index + 1 < shown.len(), egui::Button::new("⬇")).clicked() {
+                    self.graphs.swap(index, index + 1);
+                }
+            });
+        }
+        for kind in GraphKind::ALL.iter().filter(|kind| !shown.contains(kind)) {
+            let mut visible = false;
+            if ui.checkbox(&mut visible, kind.title()).changed() {
+                self.graphs.push(*kind);
+            }
         }
     }
 }
 
 impl Settings {
+    /// Writes the settings to a JSON file, to carry them to another machine.
+    pub fn export(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| e.to_string())
+    }
+
+    /// Replaces the settings with the ones of a file written by `export`.
+    pub fn import(&mut self, path: &Path) -> Result<(), String> {
+        let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let settings: Settings = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        *self = Settings {
+            view: std::mem::take(&mut self.view),
+            ..settings
+        };
+        self.migrate();
+        Ok(())
+    }
+
+    /// Carries the settings saved by older versions over to their current
+    /// form. The single refresh interval, only ever saved without
+    /// `refresh_intervals`, becomes the interval of everything.
+    pub fn migrate(&mut self) {
+        if let Some(interval) = self.update_interval_ms.take() {
+            let intervals = &mut self.refresh_intervals;
+            intervals.processes_ms = interval;
+            intervals.graphs_ms = interval;
+            intervals.sensors_ms = interval;
+        }
+    }
+
+    /// Applies the appearance settings, if they changed since last frame.
+    pub fn apply(&self, ctx: &egui::Context, frame: &eframe::Frame) {
+        let mut visuals = self.theme.visuals(frame.info().system_theme);
+        if let Some(accent) = self.accent_color {
+            visuals.selection.bg_fill = accent;
+            visuals.hyperlink_color = accent;
+        }
+        visuals.striped = self.table_style.striped;
+        if ctx.style().visuals != visuals {
+            ctx.set_visuals(visuals);
+        }
+        let pixels_per_point = frame.info().native_pixels_per_point.unwrap_or(1.0) * self.ui_scale;
+        if ctx.pixels_per_point() != pixels_per_point {
+            ctx.set_pixels_per_point(pixels_per_point);
+        }
+        if ctx.style().text_styles[&egui::TextStyle::Body].size != self.font_size {
+            let factor = self.font_size / DEFAULT_FONT_SIZE;
+            let mut style = (*ctx.style()).clone();
+            style.text_styles = egui::Style::default()
+                .text_styles
+                .into_iter()
+                .map(|(text_style, font)| (text_style, egui::FontId::new(font.size * factor, font.family)))
+                .collect();
+            ctx.set_style(style);
+        }
+    }
+
     pub fn settings_view(
         &mut self,
         ctx: &egui::Context,
@@ -22,12 +723,299 @@ impl Settings {
     ) {
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.horizontal(|ui| {
-                ui.label("Update interval (in milliseconds)");
-                ui.add(DragValue::new(&mut self.update_interval_ms)
-                    .speed(1.0)
-                    .suffix("ms")
-                );
-            })
+                ui.label("Search:");
+                ui.text_edit_singleline(&mut self.view.filter);
+            });
+            // While searching, matching settings of all the tabs are shown.
+            let filter = self.view.filter.to_lowercase();
+            if filter.is_empty() {
+                ui.horizontal(|ui| {
+                    for category in Category::ALL {
+                        ui.selectable_value(&mut self.view.category, category, category.label());
+                    }
+                });
+            }
+            ui.separator();
+
+            let rows = rows()
+                .into_iter()
+                .filter(|(category, label, _)| {
+                    if filter.is_empty() {
+                        *category == self.view.category
+                    } else {
+                        label.to_lowercase().contains(&filter)
+                    }
+                })
+                .collect::<Vec<_>>();
+            if rows.is_empty() {
+                ui.label("Nothing to configure here.");
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("settings").num_columns(2).show(ui, |ui| {
+                    for (_, label, row) in rows {
+                        ui.label(label);
+                        ui.vertical(|ui| row(self, ui));
+                        ui.end_row();
+                    }
+                });
+            });
         });
     }
 }
+
+fn rows() -> Vec<Row> {
+    vec![
+        (Category::General, "Process list update interval", |settings, ui| {
+            interval_ui(ui, &mut settings.refresh_intervals.processes_ms);
+        }),
+        (Category::General, "Graphs update interval", |settings, ui| {
+            interval_ui(ui, &mut settings.refresh_intervals.graphs_ms);
+        }),
+        (Category::General, "Sensors update interval", |settings, ui| {
+            interval_ui(ui, &mut settings.refresh_intervals.sensors_ms);
+        }),
+        (Category::General, "Refresh processes in background", |settings, ui| {
+            ui.checkbox(&mut settings.refresh_intervals.processes_in_background, "")
+                .on_hover_text("Needed by the process graphs. Disable it to lower the overhead of the task manager.");
+        }),
+        (Category::General, "Open on", |settings, ui| {
+            ui.horizontal(|ui| {
+                for view in DefaultView::ALL {
+                    ui.radio_value(&mut settings.default_view, view, view.label());
+                }
+            });
+        }),
+        (Category::General, "Window", |settings, ui| {
+            ui.checkbox(&mut settings.window.start_minimized, "Start minimized");
+            ui.checkbox(&mut settings.window.minimize_on_close, "Minimize instead of closing")
+                .on_hover_text("With the tray icon, the window is hidden in the tray instead. Use File > Quit to quit.");
+            if ui.checkbox(&mut settings.window.launch_on_login, "Launch on login").changed() {
+                settings.view.autostart_error = autostart::set_enabled(settings.window.launch_on_login).err();
+                if settings.view.autostart_error.is_some() {
+                    settings.window.launch_on_login = !settings.window.launch_on_login;
+                }
+            }
+            if let Some(error) = &settings.view.autostart_error {
+                ui.label(error);
+            }
+        }),
+        (Category::General, "Keyboard shortcuts", |settings, ui| {
+            settings.keybindings.keybindings_ui(ui);
+        }),
+        (Category::General, "Import or export settings", |settings, ui| {
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut settings.view.transfer_path);
+                let path = PathBuf::from(&settings.view.transfer_path);
+                if ui.button("Export").clicked() {
+                    settings.view.transfer_status = Some(match settings.export(&path) {
+                        Ok(()) => format!("Exported to {}", path.display()),
+                        Err(error) => format!("Could not export to {}: {}", path.display(), error),
+                    });
+                }
+                if ui.button("Import").clicked() {
+                    settings.view.transfer_status = Some(match settings.import(&path) {
+                        Ok(()) => format!("Imported {}", path.display()),
+                        Err(error) => format!("Could not import {}: {}", path.display(), error),
+                    });
+                }
+            });
+            if let Some(status) = &settings.view.transfer_status {
+                ui.label(status);
+            }
+        }),
+        (Category::Appearance, "Theme", |settings, ui| {
+            ComboBox::from_id_source("theme")
+                .selected_text(settings.theme.label())
+                .show_ui(ui, |ui| {
+                    for theme in Theme::ALL {
+                        ui.selectable_value(&mut settings.theme, theme, theme.label());
+                    }
+                });
+        }),
+        (Category::Appearance, "Accent color", |settings, ui| {
+            ui.horizontal(|ui| {
+                let mut custom = settings.accent_color.is_some();
+                if ui.checkbox(&mut custom, "Custom").changed() {
+                    settings.accent_color = custom.then(|| ui.visuals().selection.bg_fill);
+                }
+                if let Some(accent) = &mut settings.accent_color {
+                    ui.color_edit_button_srgba(accent);
+                }
+            });
+        }),
+        (Category::Appearance, "Tables", |settings, ui| {
+            ui.checkbox(&mut settings.table_style.striped, "Striped rows");
+            ui.checkbox(&mut settings.table_style.grid_lines, "Grid lines");
+        }),
+        (Category::ProcessList, "Row density", |settings, ui| {
+            ui.horizontal(|ui| {
+                for density in Density::ALL {
+                    ui.radio_value(&mut settings.table_style.density, density, density.label());
+                }
+            });
+        }),
+        (Category::Appearance, "UI scale", |settings, ui| {
+            ui.add(egui::Slider::new(&mut settings.ui_scale, 0.5..=3.0).step_by(0.25));
+        }),
+        (Category::Appearance, "Font size", |settings, ui| {
+            ui.add(egui::Slider::new(&mut settings.font_size, 8.0..=32.0).suffix(" pt"));
+        }),
+        (Category::Appearance, "Sizes", |settings, ui| {
+            ComboBox::from_id_source("byte_units")
+                .selected_text(settings.byte_units.label())
+                .show_ui(ui, |ui| {
+                    for units in ByteUnits::ALL {
+                        ui.selectable_value(&mut settings.byte_units, units, units.label());
+                    }
+                });
+        }),
+        (Category::Appearance, "Temperatures", |settings, ui| {
+            ui.horizontal(|ui| {
+                for unit in TemperatureUnit::ALL {
+                    ui.radio_value(&mut settings.temperature_unit, unit, unit.symbol());
+                }
+            });
+        }),
+        (Category::Appearance, "CPU usage", |settings, ui| {
+            ComboBox::from_id_source("cpu_normalization")
+                .selected_text(settings.cpu_normalization.label())
+                .show_ui(ui, |ui| {
+                    for normalization in CpuNormalization::ALL {
+                        ui.selectable_value(&mut settings.cpu_normalization, normalization, normalization.label());
+                    }
+                });
+        }),
+        (Category::ProcessList, "Sort processes by", |settings, ui| {
+            ui.horizontal(|ui| {
+                ComboBox::from_id_source("sort_column")
+                    .selected_text(settings.sort_column.label())
+                    .show_ui(ui, |ui| {
+                        for column in Columns::ALL {
+                            ui.selectable_value(&mut settings.sort_column, column, column.label());
+                        }
+                    });
+                ui.radio_value(&mut settings.sort_order, Order::Asc, "Ascending");
+                ui.radio_value(&mut settings.sort_order, Order::Desc, "Descending");
+            });
+        }),
+        (Category::ProcessList, "Confirm before killing", |settings, ui| {
+            ui.checkbox(&mut settings.confirm_kill.single, "A process");
+            ui.checkbox(&mut settings.confirm_kill.tree, "A process tree");
+            ui.checkbox(&mut settings.confirm_kill.all, "All matching processes");
+        }),
+        (Category::Graphs, "Graphs history", |settings, ui| {
+            ComboBox::from_id_source("graph_retention")
+                .selected_text(settings.graph_retention.label())
+                .show_ui(ui, |ui| {
+                    for retention in GraphRetention::ALL {
+                        ui.selectable_value(&mut settings.graph_retention, retention, retention.label());
+                    }
+                });
+        }),
+        (Category::Graphs, "Graphs layout", |settings, ui| {
+            settings.graphs_layout.layout_ui(ui);
+        }),
+        (Category::Graphs, "Metrics recording", |settings, ui| {
+            let recording = &mut settings.recording;
+            ui.checkbox(&mut recording.enabled, "Append the CPU, memory and network usage to a file at every refresh");
+            ui.add_enabled_ui(recording.enabled, |ui| {
+                egui::Grid::new("recording").show(ui, |ui| {
+                    ui.label("File");
+                    ui.text_edit_singleline(&mut recording.path);
+                    ui.end_row();
+                    ui.label("Format");
+                    ComboBox::from_id_source("recording_format")
+                        .selected_text(recording.format.label())
+                        .show_ui(ui, |ui| {
+                            for format in RecordingFormat::ALL {
+                                if ui.selectable_value(&mut recording.format, format, format.label()).changed() {
+                                    recording.path = Path::new(&recording.path).with_extension(format.extension()).display().to_string();
+                                }
+                            }
+                        });
+                    ui.end_row();
+                    ui.label("Top processes");
+                    ui.add(DragValue::new(&mut recording.top_processes).clamp_range(0..=50))
+                        .on_hover_text("Number of most CPU hungry processes written with each sample");
+                    ui.end_row();
+                    ui.label("Rotate after");
+                    ui.add(DragValue::new(&mut recording.max_size_mib).clamp_range(1..=10_000).suffix(" MiB"));
+                    ui.end_row();
+                    ui.label("Rotated files kept");
+                    ui.add(DragValue::new(&mut recording.kept_files).clamp_range(0..=100));
+                    ui.end_row();
+                });
+            });
+        }),
+        (Category::Alerts, "Watchdog rules", |settings, ui| {
+            ui.label("Act on the processes matching a label search once they stay past a threshold.");
+            let byte_units = settings.byte_units;
+            rules::rules_ui(&mut settings.rules, ui, byte_units);
+        }),
+        (Category::Alerts, "Thresholds", |settings, ui| {
+            let unit = settings.temperature_unit;
+            let thresholds = &mut settings.thresholds;
+            // The temperatures are kept in degrees Celsius, and edited in the
+            // chosen unit.
+            let celsius = thresholds.temperature;
+            let mut temperature = unit.convert_threshold(celsius);
+            let temperature_suffix = format!(" {}", unit.symbol());
+            egui::Grid::new("thresholds").show(ui, |ui| {
+                ui.label("");
+                ui.colored_label(Level::Warning.color(), "Warning");
+                ui.colored_label(Level::Critical.color(), "Critical");
+                ui.end_row();
+                for (label, threshold, max, suffix) in [
+                    ("CPU", &mut thresholds.cpu, 100.0, "%"),
+                    ("Memory", &mut thresholds.memory, 100.0, "%"),
+                    ("Swap", &mut thresholds.swap, 100.0, "%"),
+                    ("Disk space", &mut thresholds.disk, 100.0, "%"),
+                    ("Temperature", &mut temperature, unit.convert(150.0) as f32, &temperature_suffix),
+                ] {
+                    ui.label(label);
+                    ui.add(DragValue::new(&mut threshold.warning).clamp_range(0.0..=threshold.critical).suffix(suffix));
+                    ui.add(DragValue::new(&mut threshold.critical).clamp_range(threshold.warning..=max).suffix(suffix));
+                    ui.end_row();
+                }
+            });
+            if temperature != unit.convert_threshold(celsius) {
+                thresholds.temperature = Threshold {
+                    warning: unit.celsius(temperature.warning as f64) as f32,
+                    critical: unit.celsius(temperature.critical as f64) as f32,
+                };
+            }
+        }),
+        #[cfg(feature = "notifications")]
+        (Category::Alerts, "Desktop notifications", |settings, ui| {
+            let notifications = &mut settings.notifications;
+            ui.checkbox(&mut notifications.enabled, "Notify when a metric stays past its critical threshold")
+                .on_hover_text("Also when the window is minimized, naming the process using the most of it.");
+            ui.add_enabled_ui(notifications.enabled, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("For");
+                    ui.add(DragValue::new(&mut notifications.duration_secs).clamp_range(0..=3600).suffix(" s"));
+                });
+            });
+        }),
+    ]
+}
+
+/// The settings file of the portable mode, which is used instead of the
+/// platform data directory when it exists next to the executable.
+pub fn portable_path() -> Option<PathBuf> {
+    let path = std::env::current_exe().ok()?.with_file_name("task-manager.json");
+    path.exists().then_some(path)
+}
+
+/// When a file was last modified, to notice edits made outside of the app.
+pub fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn interval_ui(ui: &mut egui::Ui, interval: &mut u64) {
+    ui.add(DragValue::new(interval)
+        .speed(1.0)
+        .clamp_range(REFRESH_INTERVAL_MS)
+        .suffix("ms")
+    );
+}
@@ -0,0 +1,148 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use sysinfo::{CpuExt, CpuRefreshKind, DiskExt, PidExt, ProcessExt, ProcessRefreshKind, System, SystemExt, UserExt};
+
+use crate::cli::DumpFormat;
+
+/// The processes and the usage of the system at one refresh, printed by
+/// `--dump` without opening a window. Sizes are in bytes.
+#[derive(Serialize)]
+pub struct Snapshot {
+    /// UNIX timestamp, in seconds.
+    time: u64,
+    system: SystemStats,
+    processes: Vec<ProcessStats>,
+}
+
+#[derive(Serialize)]
+struct SystemStats {
+    hostname: String,
+    uptime_secs: u64,
+    /// In percent.
+    cpu: f32,
+    load_average: [f64; 3],
+    memory_used: u64,
+    memory_total: u64,
+    swap_used: u64,
+    swap_total: u64,
+    disk_used: u64,
+    disk_total: u64,
+    processes: usize,
+}
+
+#[derive(Serialize)]
+struct ProcessStats {
+    pid: u32,
+    parent: Option<u32>,
+    name: String,
+    owner: Option<String>,
+    /// In percent of a core.
+    cpu: f32,
+    memory: u64,
+    status: String,
+}
+
+impl Snapshot {
+    /// Refreshes the system twice, as the CPU usage is measured between two
+    /// refreshes.
+    pub fn collect() -> Self {
+        let mut system = System::new_all();
+        std::thread::sleep(System::MINIMUM_CPU_UPDATE_INTERVAL);
+        system.refresh_cpu_specifics(CpuRefreshKind::everything());
+        system.refresh_processes_specifics(ProcessRefreshKind::new().with_cpu().with_user());
+
+        let load = system.load_average();
+        let disks = system.disks();
+        let mut processes = system
+            .processes()
+            .values()
+            .map(|process| ProcessStats {
+                pid: process.pid().as_u32(),
+                parent: process.parent().map(|parent| parent.as_u32()),
+                name: process.name().to_string(),
+                owner: process
+                    .user_id()
+                    .and_then(|uid| system.get_user_by_id(uid))
+                    .map(|user| user.name().to_string()),
+                cpu: process.cpu_usage(),
+                memory: process.memory(),
+                status: process.status().to_string(),
+            })
+            .collect::<Vec<_>>();
+        processes.sort_by_key(|process| process.pid);
+        Self {
+            time: SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or_default(),
+            system: SystemStats {
+                hostname: system.host_name().unwrap_or_default(),
+                uptime_secs: system.uptime(),
+                cpu: system.global_cpu_info().cpu_usage(),
+                load_average: [load.one, load.five, load.fifteen],
+                memory_used: system.used_memory(),
+                memory_total: system.total_memory(),
+                swap_used: system.used_swap(),
+                swap_total: system.total_swap(),
+                disk_used: disks.iter().map(|disk| disk.total_space() - disk.available_space()).sum(),
+                disk_total: disks.iter().map(|disk| disk.total_space()).sum(),
+                processes: processes.len(),
+            },
+            processes,
+        }
+    }
+
+    pub fn format(&self, format: DumpFormat) -> String {
+        match format {
+            DumpFormat::Json => serde_json::to_string_pretty(self).unwrap() + "\n",
+            DumpFormat::Csv => self.csv(),
+        }
+    }
+
+    /// Two tables separated by an empty line: the metrics of the system,
+    /// then the processes.
+    fn csv(&self) -> String {
+        let system = &self.system;
+        let metrics = [
+            ("time", self.time.to_string()),
+            ("hostname", system.hostname.clone()),
+            ("uptime_secs", system.uptime_secs.to_string()),
+            ("cpu", system.cpu.to_string()),
+            ("load_average_1", system.load_average[0].to_string()),
+            ("load_average_5", system.load_average[1].to_string()),
+            ("load_average_15", system.load_average[2].to_string()),
+            ("memory_used", system.memory_used.to_string()),
+            ("memory_total", system.memory_total.to_string()),
+            ("swap_used", system.swap_used.to_string()),
+            ("swap_total", system.swap_total.to_string()),
+            ("disk_used", system.disk_used.to_string()),
+            ("disk_total", system.disk_total.to_string()),
+            ("processes", system.processes.to_string()),
+        ];
+        let mut csv = String::from("metric,value\n");
+        for (metric, value) in metrics {
+            csv += &format!("{metric},{}\n", csv_field(&value));
+        }
+        csv += "\npid,parent,name,owner,cpu,memory,status\n";
+        for process in &self.processes {
+            csv += &format!(
+                "{},{},{},{},{},{},{}\n",
+                process.pid,
+                process.parent.map(|parent| parent.to_string()).unwrap_or_default(),
+                csv_field(&process.name),
+                csv_field(process.owner.as_deref().unwrap_or_default()),
+                process.cpu,
+                process.memory,
+                csv_field(&process.status)
+            );
+        }
+        csv
+    }
+}
+
+/// Quotes a field if it has a comma, a quote or a line break.
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
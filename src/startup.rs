@@ -0,0 +1,85 @@
+use crate::autostart::{self, Entry};
+
+/// Programs started on login: XDG autostart entries, and the services of
+/// the user when built with the `systemd` feature.
+#[derive(Default)]
+pub struct StartupState {
+    entries: Vec<Entry>,
+    #[cfg(feature = "systemd")]
+    units: Vec<(String, bool)>,
+    /// Why the services could not be listed.
+    #[cfg(feature = "systemd")]
+    units_error: Option<String>,
+    /// Connection to the session bus, kept between the calls.
+    #[cfg(feature = "systemd")]
+    buses: crate::services::Buses,
+    loaded: bool,
+    error: Option<String>,
+}
+
+impl StartupState {
+    fn load(&mut self) {
+        self.entries = autostart::entries();
+        #[cfg(feature = "systemd")]
+        {
+            match self.buses.with(true, crate::services::user_unit_files) {
+                Ok(units) => {
+                    self.units = units;
+                    self.units_error = None;
+                }
+                Err(error) => self.units_error = Some(error),
+            }
+        }
+        self.loaded = true;
+    }
+
+    pub fn startup_view(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if !self.loaded {
+            self.load();
+        }
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if ui.button("Reload").clicked() {
+                self.load();
+            }
+            if let Some(error) = &self.error {
+                ui.label(error);
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.heading("Autostart entries");
+                let mut toggled = None;
+                egui::Grid::new("autostart").num_columns(2).show(ui, |ui| {
+                    for entry in &self.entries {
+                        let mut enabled = entry.enabled;
+                        if ui.checkbox(&mut enabled, &entry.name).changed() {
+                            toggled = Some((entry, enabled));
+                        }
+                        ui.label(&entry.exec);
+                        ui.end_row();
+                    }
+                });
+                if let Some((entry, enabled)) = toggled {
+                    self.error = autostart::set_entry_enabled(entry, enabled).err();
+                    self.loaded = false;
+                }
+
+                #[cfg(feature = "systemd")]
+                {
+                    ui.heading("User services");
+                    if let Some(error) = &self.units_error {
+                        ui.label(format!("Could not list the services: {error}"));
+                    }
+                    for (unit, enabled) in &self.units {
+                        let mut enabled = *enabled;
+                        if ui.checkbox(&mut enabled, unit).changed() {
+                            self.error = self
+                                .buses
+                                .with(true, |connection| crate::services::set_user_unit_enabled(connection, unit, enabled))
+                                .err();
+                            self.loaded = false;
+                        }
+                    }
+                }
+            });
+        });
+    }
+}
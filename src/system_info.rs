@@ -0,0 +1,54 @@
+use std::sync::{Arc, Mutex};
+
+use sysinfo::{CpuExt, System, SystemExt};
+
+use crate::{format::format_time, settings::Settings};
+
+/// Shows what the machine is: its name, operating system and hardware.
+pub fn system_info_view(
+    ctx: &egui::Context,
+    _frame: &mut eframe::Frame,
+    system: Arc<Mutex<System>>,
+    settings: Arc<Mutex<Settings>>,
+) {
+    let byte_units = settings.lock().unwrap().byte_units;
+    let system = system.lock().unwrap();
+    let unknown = || "Unknown".to_string();
+    let (gib, gib_name) = byte_units.unit(3);
+    let rows = [
+        ("Hostname", system.host_name().unwrap_or_else(unknown)),
+        ("Operating system", system.long_os_version().unwrap_or_else(unknown)),
+        ("Kernel", system.kernel_version().unwrap_or_else(unknown)),
+        ("Uptime", format_duration(system.uptime())),
+        ("Boot time", format_time(system.boot_time() as i64)),
+        ("CPU", system.global_cpu_info().brand().to_string()),
+        (
+            "Cores",
+            match system.physical_core_count() {
+                Some(physical) => format!("{} ({} logical)", physical, system.cpus().len()),
+                None => format!("{} logical", system.cpus().len()),
+            },
+        ),
+        ("Memory", format!("{:.1} {}", system.total_memory() as f64 / gib, gib_name)),
+        ("Swap", format!("{:.1} {}", system.total_swap() as f64 / gib, gib_name)),
+    ];
+    egui::CentralPanel::default().show(ctx, |ui| {
+        egui::Grid::new("system_info").num_columns(2).show(ui, |ui| {
+            for (label, value) in rows {
+                ui.label(label);
+                ui.label(value);
+                ui.end_row();
+            }
+        });
+    });
+}
+
+/// Formats a number of seconds as days, hours and minutes.
+fn format_duration(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{days}d {hours}h {minutes}m")
+    } else {
+        format!("{hours}h {minutes}m")
+    }
+}
@@ -0,0 +1,334 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use sysinfo::{CpuExt, System, SystemExt};
+use zbus::{
+    blocking::{Connection, ConnectionBuilder, Proxy},
+    dbus_interface,
+    zvariant::{OwnedObjectPath, OwnedValue, StructureBuilder, Value},
+};
+
+use crate::settings::Settings;
+
+/// What was asked from the tray, handled by the app on next frame.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TrayRequest {
+    ToggleWindow,
+    Quit,
+}
+
+/// Size of the icon showing the usage, in pixels.
+const ICON_SIZE: i32 = 22;
+
+/// Menu items, identified as in the dbusmenu protocol, 0 being the root.
+const TOGGLE_ITEM: i32 = 1;
+const SEPARATOR_ITEM: i32 = 2;
+const QUIT_ITEM: i32 = 3;
+
+/// Icon in ARGB32, with its width and height, as given to the tray host.
+type Pixmap = (i32, i32, Vec<u8>);
+
+/// A menu item and its children: `(ia{sv}av)`.
+type Layout = (i32, HashMap<String, OwnedValue>, Vec<OwnedValue>);
+
+#[derive(Default)]
+struct State {
+    /// Whether the window is shown, to label the menu item.
+    visible: bool,
+    requests: Vec<TrayRequest>,
+    tooltip: String,
+    icon: Vec<u8>,
+    /// Incremented when the menu changes, for the host to fetch it again.
+    revision: u32,
+}
+
+/// An icon in the system tray, through the StatusNotifierItem protocol of
+/// freedesktop, showing the CPU and memory usage.
+pub struct Tray {
+    connection: Connection,
+    state: Arc<Mutex<State>>,
+}
+
+impl Tray {
+    /// Registers the icon and keeps it up to date with the usage. Fails if
+    /// no tray is running.
+    pub fn new(ctx: egui::Context, system: Arc<Mutex<System>>, settings: Arc<Mutex<Settings>>) -> zbus::Result<Self> {
+        let state = Arc::new(Mutex::new(State {
+            visible: true,
+            ..Default::default()
+        }));
+        let name = format!("org.kde.StatusNotifierItem-{}-1", std::process::id());
+        let connection = ConnectionBuilder::session()?
+            .name(name.as_str())?
+            .serve_at("/StatusNotifierItem", Item { state: state.clone(), ctx: ctx.clone() })?
+            .serve_at("/MenuBar", Menu { state: state.clone(), ctx })?
+            .build()?;
+        Proxy::new(
+            &connection,
+            "org.kde.StatusNotifierWatcher",
+            "/StatusNotifierWatcher",
+            "org.kde.StatusNotifierWatcher",
+        )?
+        .call_method("RegisterStatusNotifierItem", &(name.as_str()))?;
+
+        thread::spawn({
+            let connection = connection.clone();
+            let state = state.clone();
+            move || loop {
+                let (interval, byte_units) = {
+                    let settings = settings.lock().unwrap();
+                    (settings.refresh_intervals.graphs_ms, settings.byte_units)
+                };
+                thread::sleep(Duration::from_millis(interval));
+                let (cpu, memory, used, total) = {
+                    let system = system.lock().unwrap();
+                    let cpu = system.global_cpu_info().cpu_usage();
+                    let memory = system.used_memory() as f32 / system.total_memory().max(1) as f32 * 100.0;
+                    (cpu, memory, system.used_memory(), system.total_memory())
+                };
+                let (gib, gib_name) = byte_units.unit(3);
+                {
+                    let mut state = state.lock().unwrap();
+                    state.tooltip = format!(
+                        "CPU: {cpu:.0}%\nMemory: {:.1} / {:.1} {gib_name}",
+                        used as f64 / gib,
+                        total as f64 / gib
+                    );
+                    state.icon = usage_icon(cpu, memory);
+                }
+                for signal in ["NewToolTip", "NewIcon"] {
+                    if connection
+                        .emit_signal(None::<()>, "/StatusNotifierItem", "org.kde.StatusNotifierItem", signal, &())
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(Self { connection, state })
+    }
+
+    /// Takes what was asked from the tray since last call.
+    pub fn requests(&self) -> Vec<TrayRequest> {
+        std::mem::take(&mut self.state.lock().unwrap().requests)
+    }
+
+    /// Tells whether the window is shown, for the menu to offer showing or
+    /// hiding it.
+    pub fn set_visible(&self, visible: bool) {
+        let revision = {
+            let mut state = self.state.lock().unwrap();
+            state.visible = visible;
+            state.revision += 1;
+            state.revision
+        };
+        let _ = self
+            .connection
+            .emit_signal(None::<()>, "/MenuBar", "com.canonical.dbusmenu", "LayoutUpdated", &(revision, 0i32));
+    }
+}
+
+/// Two bars, for the CPU and the memory usage in percents.
+fn usage_icon(cpu: f32, memory: f32) -> Vec<u8> {
+    let bars = [(3..10, cpu, [0xff, 0x4a, 0x90, 0xd9]), (12..19, memory, [0xff, 0x5c, 0xb8, 0x5c])];
+    let mut pixels = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let pixel = bars.iter().find(|(columns, ..)| columns.contains(&x)).map(|(_, usage, color)| {
+                // Rows from the bottom, leaving a pixel of margin.
+                let filled = ((ICON_SIZE - 2) as f32 * usage.clamp(0.0, 100.0) / 100.0).round() as i32;
+                if ICON_SIZE - 1 - y <= filled && y < ICON_SIZE - 1 {
+                    *color
+                } else {
+                    [0x60, 0x80, 0x80, 0x80]
+                }
+            });
+            pixels.extend(pixel.unwrap_or([0; 4]));
+        }
+    }
+    pixels
+}
+
+struct Item {
+    state: Arc<Mutex<State>>,
+    ctx: egui::Context,
+}
+
+#[dbus_interface(name = "org.kde.StatusNotifierItem")]
+impl Item {
+    fn activate(&self, _x: i32, _y: i32) {
+        self.state.lock().unwrap().requests.push(TrayRequest::ToggleWindow);
+        self.ctx.request_repaint();
+    }
+
+    fn secondary_activate(&self, _x: i32, _y: i32) {}
+
+    fn scroll(&self, _delta: i32, _orientation: &str) {}
+
+    #[dbus_interface(property)]
+    fn category(&self) -> &str {
+        "SystemServices"
+    }
+
+    #[dbus_interface(property)]
+    fn id(&self) -> &str {
+        "task-manager"
+    }
+
+    #[dbus_interface(property)]
+    fn title(&self) -> &str {
+        "Task Manager"
+    }
+
+    #[dbus_interface(property)]
+    fn status(&self) -> &str {
+        "Active"
+    }
+
+    #[dbus_interface(property)]
+    fn icon_name(&self) -> &str {
+        "utilities-system-monitor"
+    }
+
+    #[dbus_interface(property)]
+    fn icon_pixmap(&self) -> Vec<Pixmap> {
+        let state = self.state.lock().unwrap();
+        if state.icon.is_empty() {
+            return vec![];
+        }
+        vec![(ICON_SIZE, ICON_SIZE, state.icon.clone())]
+    }
+
+    #[dbus_interface(property)]
+    fn tool_tip(&self) -> (String, Vec<Pixmap>, String, String) {
+        let tooltip = self.state.lock().unwrap().tooltip.clone();
+        (String::new(), vec![], "Task Manager".to_string(), tooltip)
+    }
+
+    #[dbus_interface(property)]
+    fn item_is_menu(&self) -> bool {
+        false
+    }
+
+    #[dbus_interface(property)]
+    fn menu(&self) -> OwnedObjectPath {
+        OwnedObjectPath::try_from("/MenuBar").unwrap()
+    }
+}
+
+/// The menu of the icon, through the dbusmenu protocol.
+struct Menu {
+    state: Arc<Mutex<State>>,
+    ctx: egui::Context,
+}
+
+impl Menu {
+    fn properties(&self, id: i32) -> HashMap<String, OwnedValue> {
+        let mut properties = HashMap::new();
+        let mut set = |name: &str, value: Value<'_>| {
+            properties.insert(name.to_string(), OwnedValue::from(value));
+        };
+        match id {
+            0 => set("children-display", Value::from("submenu")),
+            TOGGLE_ITEM => {
+                let visible = self.state.lock().unwrap().visible;
+                set("label", Value::from(if visible { "Hide window" } else { "Show window" }));
+            }
+            SEPARATOR_ITEM => set("type", Value::from("separator")),
+            QUIT_ITEM => set("label", Value::from("Quit")),
+            _ => {}
+        }
+        properties
+    }
+
+    fn clicked(&self, id: i32) {
+        let request = match id {
+            TOGGLE_ITEM => TrayRequest::ToggleWindow,
+            QUIT_ITEM => TrayRequest::Quit,
+            _ => return,
+        };
+        self.state.lock().unwrap().requests.push(request);
+        self.ctx.request_repaint();
+    }
+}
+
+#[dbus_interface(name = "com.canonical.dbusmenu")]
+impl Menu {
+    fn get_layout(&self, parent_id: i32, _recursion_depth: i32, _property_names: Vec<String>) -> (u32, Layout) {
+        let revision = self.state.lock().unwrap().revision;
+        let children = match parent_id {
+            0 => [TOGGLE_ITEM, SEPARATOR_ITEM, QUIT_ITEM]
+                .into_iter()
+                .map(|id| {
+                    let child = StructureBuilder::new()
+                        .add_field(id)
+                        .add_field(self.properties(id))
+                        .add_field(Vec::<OwnedValue>::new())
+                        .build();
+                    OwnedValue::from(Value::from(child))
+                })
+                .collect(),
+            _ => vec![],
+        };
+        (revision, (parent_id, self.properties(parent_id), children))
+    }
+
+    fn get_group_properties(&self, ids: Vec<i32>, _property_names: Vec<String>) -> Vec<(i32, HashMap<String, OwnedValue>)> {
+        let ids = if ids.is_empty() { vec![0, TOGGLE_ITEM, SEPARATOR_ITEM, QUIT_ITEM] } else { ids };
+        ids.into_iter().map(|id| (id, self.properties(id))).collect()
+    }
+
+    fn get_property(&self, id: i32, name: &str) -> zbus::fdo::Result<OwnedValue> {
+        self.properties(id)
+            .remove(name)
+            .ok_or_else(|| zbus::fdo::Error::InvalidArgs(format!("No property {name} on item {id}")))
+    }
+
+    fn event(&self, id: i32, event_id: &str, _data: OwnedValue, _timestamp: u32) {
+        if event_id == "clicked" {
+            self.clicked(id);
+        }
+    }
+
+    fn event_group(&self, events: Vec<(i32, String, OwnedValue, u32)>) -> Vec<i32> {
+        for (id, event_id, ..) in events {
+            if event_id == "clicked" {
+                self.clicked(id);
+            }
+        }
+        vec![]
+    }
+
+    fn about_to_show(&self, _id: i32) -> bool {
+        false
+    }
+
+    fn about_to_show_group(&self, _ids: Vec<i32>) -> (Vec<i32>, Vec<i32>) {
+        (vec![], vec![])
+    }
+
+    #[dbus_interface(property)]
+    fn version(&self) -> u32 {
+        3
+    }
+
+    #[dbus_interface(property)]
+    fn text_direction(&self) -> &str {
+        "ltr"
+    }
+
+    #[dbus_interface(property)]
+    fn status(&self) -> &str {
+        "normal"
+    }
+
+    #[dbus_interface(property)]
+    fn icon_theme_path(&self) -> Vec<String> {
+        vec![]
+    }
+}